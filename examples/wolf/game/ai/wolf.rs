@@ -42,7 +42,8 @@ pub(crate) fn construct_hunter_ai(app: &mut App) {
                 // prefer closer targets
                 .add_consideration(
                     Consideration::targeted(distance_to)
-                        .with_response_curve(Linear::new(-0.001).shifted(0.0, 1.0)),
+                        .with_input_range(0.0, 1000.0)
+                        .with_response_curve(Linear::new(-1.0).shifted(0.0, 1.0)),
                 )
                 // if we have energy
                 .add_consideration(
@@ -63,7 +64,8 @@ pub(crate) fn construct_hunter_ai(app: &mut App) {
                 // & prefer closer targets
                 .add_consideration(
                     Consideration::targeted(distance_to)
-                        .with_response_curve(Linear::new(-0.001).shifted(0.0, 1.0)),
+                        .with_input_range(0.0, 1000.0)
+                        .with_response_curve(Linear::new(-1.0).shifted(0.0, 1.0)),
                 )
                 // & prefer if we are hungry
                 .add_consideration(
@@ -83,14 +85,14 @@ pub(crate) fn construct_hunter_ai(app: &mut App) {
                 )
                 // & prefer closer targets
                 .add_consideration(
-                    Consideration::targeted(distance_to).with_response_curve(
-                        PiecewiseLinear::new([
+                    Consideration::targeted(distance_to)
+                        .with_input_range(0.0, 256.0)
+                        .with_response_curve(PiecewiseLinear::new([
                             (0.0, 1.0),
-                            (32.0, 1.0),
-                            (48.0, 0.9),
-                            (256.0, 0.75),
-                        ]),
-                    ),
+                            (0.125, 1.0),
+                            (0.1875, 0.9),
+                            (1.0, 0.75),
+                        ])),
                 ),
         )
         .add_decision(
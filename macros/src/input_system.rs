@@ -56,7 +56,15 @@ pub(crate) fn input_system(
             for (entity, mut ai_meta #(, #component_arg_idents)*) in query_input_system.iter_mut() {
                 let _span = bevy::prelude::debug_span!(target: "bevy_utility_ai", "", entity = entity.index()).entered();
 
-                let ai_definition = &res_ai_definitions.map[&ai_meta.ai_definition];
+                if !ai_meta.evaluate_this_frame {
+                    bevy::prelude::debug!(target: "bevy_utility_ai", "skipped as entity is not evaluated this frame");
+                    continue;
+                }
+
+                let Some(ai_definition) = res_ai_definitions.map.get(&ai_meta.ai_definition) else {
+                    bevy::prelude::debug!(target: "bevy_utility_ai", "skipped as the entity's AI definition is not registered");
+                    continue;
+                };
 
                 if !ai_definition.requires_simple_input(&key) {
                     bevy::prelude::debug!(target: "bevy_utility_ai", "skipped calculating inputs for this entity");
@@ -5,12 +5,63 @@ use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::{quote, ToTokens};
 use syn::__private::TokenStream2;
-use syn::{Error, FnArg, ItemFn};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{Error, Expr, FnArg, ItemFn, MetaNameValue, Path, Token};
+
+/// The optional arguments of the macro, e.g. `#[targeted_input_system(max_range = 64.0)]`.
+struct TargetedInputArgs {
+    max_range: Option<Expr>,
+    spatial_index: Option<Path>,
+}
+
+fn parse_args(args: TokenStream) -> Result<TargetedInputArgs, Error> {
+    let mut parsed_args = TargetedInputArgs {
+        max_range: None,
+        spatial_index: None,
+    };
+    let name_values =
+        Punctuated::<MetaNameValue, Token![,]>::parse_terminated.parse(args)?;
+
+    for name_value in name_values {
+        if name_value.path.is_ident("max_range") {
+            parsed_args.max_range = Some(name_value.value);
+        } else if name_value.path.is_ident("spatial_index") {
+            match name_value.value {
+                Expr::Path(path) => parsed_args.spatial_index = Some(path.path),
+                value => {
+                    return Err(Error::new_spanned(
+                        value.into_token_stream(),
+                        "Expected the type of a SpatialIndex resource".to_string(),
+                    ))
+                }
+            }
+        } else {
+            return Err(Error::new_spanned(
+                name_value.path.into_token_stream(),
+                "Unknown argument, expected one of max_range, spatial_index".to_string(),
+            ));
+        }
+    }
+
+    if parsed_args.max_range.is_none() {
+        if let Some(spatial_index) = parsed_args.spatial_index {
+            return Err(Error::new_spanned(
+                spatial_index.into_token_stream(),
+                "A spatial_index requires a max_range".to_string(),
+            ));
+        }
+    }
+
+    Ok(parsed_args)
+}
 
 pub(crate) fn targeted_input_system(
-    _: TokenStream,
+    args: TokenStream,
     input: TokenStream,
 ) -> Result<TokenStream, Error> {
+    let args = parse_args(args)?;
+
     let item_fn = match syn::parse::<ItemFn>(input) {
         Ok(ast) => ast,
         Err(err) => return Err(err),
@@ -146,11 +197,72 @@ pub(crate) fn targeted_input_system(
         .map(|ParsedInput { ident, tokens, .. }| quote! { #ident: #tokens })
         .collect();
 
+    // when there is a max_range, only targets within range of the subject are considered
+    let (range_args, subject_transform, targets_iter) = match args.max_range {
+        None => (
+            TokenStream2::new(),
+            TokenStream2::new(),
+            quote! { q_target.iter() },
+        ),
+        Some(max_range) => {
+            let spatial_index = args
+                .spatial_index
+                .map_or(quote! { bevy_utility_ai::spatial::TransformGrid }, |path| {
+                    path.into_token_stream()
+                });
+            (
+                quote! {
+                    res_spatial_index: Option<bevy::prelude::Res<#spatial_index>>,
+                    q_target_transform: bevy::prelude::Query<(bevy::prelude::Entity, &bevy::prelude::Transform)>,
+                },
+                quote! { , subject_transform },
+                quote! {
+                    {
+                        let max_range: f32 = #max_range;
+                        let position = subject_transform.translation;
+                        let target_candidates: Vec<bevy::prelude::Entity> = match &res_spatial_index {
+                            Some(spatial_index) => bevy_utility_ai::spatial::SpatialIndex::entities_within(
+                                spatial_index.as_ref(), position, max_range
+                            ),
+                            None => q_target_transform
+                                .iter()
+                                .filter(|(_, transform)| {
+                                    transform.translation.distance_squared(position) <= max_range * max_range
+                                })
+                                .map(|(entity, _)| entity)
+                                .collect(),
+                        };
+                        // forget the scores of targets which are no longer in range
+                        score_map.clear();
+                        q_target.iter_many(target_candidates)
+                    }
+                },
+            )
+        }
+    };
+    // subjects without a Transform can't be in range of any target
+    let (subject_transform_type, subject_transform_check) = match subject_transform
+        .is_empty()
+    {
+        true => (TokenStream2::new(), TokenStream2::new()),
+        false => (
+            quote! { , Option<&bevy::prelude::Transform> },
+            quote! {
+                let Some(subject_transform) = subject_transform else {
+                    bevy::prelude::debug!(target: "bevy_utility_ai", "skipped as entity has no Transform");
+                    score_map.clear();
+                    continue;
+                };
+            },
+        ),
+    };
+
     let output = quote! {
         #vis fn #name(
-            mut q_subject: bevy::prelude::Query<(bevy::prelude::Entity, &mut bevy_utility_ai::AIMeta #(, &#subject_arg_types)*)>,
+            mut q_subject: bevy::prelude::Query<(bevy::prelude::Entity, &mut bevy_utility_ai::AIMeta #(, &#subject_arg_types)* #subject_transform_type)>,
             q_target: bevy::prelude::Query<(bevy::prelude::Entity #(, &#target_arg_types)*)>,
             res_ai_definitions: bevy::prelude::Res<bevy_utility_ai::AIDefinitions>,
+            #range_args
             #[cfg(debug_assertions)]
             mut event_writer: bevy::prelude::EventWriter<bevy_utility_ai::events::InputCalculatedEvent>,
             archetypes: &bevy::ecs::archetype::Archetypes,
@@ -161,10 +273,18 @@ pub(crate) fn targeted_input_system(
             let _span = bevy::prelude::debug_span!(target: "bevy_utility_ai", "Calculating Targeted Input", input = #quoted_name).entered();
             let key = bevy_utility_ai::utils::type_id_of(&#name);
 
-            for (subject_entity_id, mut ai_meta #(, #subject_arg_names)*) in q_subject.iter_mut() {
+            for (subject_entity_id, mut ai_meta #(, #subject_arg_names)* #subject_transform) in q_subject.iter_mut() {
                 let _span = bevy::prelude::debug_span!(target: "bevy_utility_ai", "", entity = subject_entity_id.index()).entered();
 
-                let ai_definition = res_ai_definitions.map.get(&ai_meta.ai_definition).unwrap();
+                if !ai_meta.evaluate_this_frame {
+                    bevy::prelude::debug!(target: "bevy_utility_ai", "skipped as entity is not evaluated this frame");
+                    continue;
+                }
+
+                let Some(ai_definition) = res_ai_definitions.map.get(&ai_meta.ai_definition) else {
+                    bevy::prelude::debug!(target: "bevy_utility_ai", "skipped as the entity's AI definition is not registered");
+                    continue;
+                };
                 if !ai_definition.requires_targeted_input(&key) {
                     bevy::prelude::debug!("skipped calculating inputs for this entity");
                     continue;
@@ -178,9 +298,11 @@ pub(crate) fn targeted_input_system(
                     .entry(key)
                     .or_insert(bevy::utils::HashMap::new());
 
+                #subject_transform_check
+
                 #subject_data_line
 
-                for (target_entity_id #(, #target_arg_names)*) in q_target.iter() {
+                for (target_entity_id #(, #target_arg_names)*) in #targets_iter {
                     let _span = bevy::prelude::debug_span!(target: "bevy_utility_ai", "", target_entity = target_entity_id.index()).entered();
 
                     let matches_filters = {
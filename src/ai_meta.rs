@@ -1,6 +1,8 @@
+use crate::systems::update_action::ReflectAction;
 use bevy::prelude::{Component, Entity};
 use bevy::utils::HashMap;
 use std::any::TypeId;
+use uuid::Uuid;
 
 /// A Component which stores all the state required by the various AI systems relating to an Entity.
 #[derive(Component, Clone)]
@@ -22,6 +24,27 @@ pub struct AIMeta {
     pub current_action_name: String,
     /// The current target, only exists if the current action is a targeted one.
     pub current_target: Option<Entity>,
+    /// The elapsed time in seconds at which the current action was started.
+    pub current_action_started: Option<f32>,
+    /// The reflection data of the current action, used to remove it when it changes.
+    pub(crate) current_action_reflect: Option<ReflectAction>,
+    /// Whether this entity is evaluated this frame, see [`UpdateBudget`].
+    ///
+    /// [`UpdateBudget`]: crate::systems::budget::UpdateBudget
+    pub evaluate_this_frame: bool,
+    /// The elapsed time in seconds at which this entity was last scheduled for evaluation.
+    pub last_evaluated: Option<f32>,
+    /// The decisions this entity may not perform until their cooldown expires.
+    pub cooldowns: DecisionCooldowns,
+    /// Whether the completion of the current action has been handled, see [`ActionState`].
+    ///
+    /// [`ActionState`]: crate::ActionState
+    pub(crate) current_action_completed: bool,
+    /// The current actions of the named action layers, see [`Decision::in_layer`]. The
+    /// current action of the default layer is stored on the AIMeta itself.
+    ///
+    /// [`Decision::in_layer`]: crate::Decision::in_layer
+    pub layers: HashMap<String, ActionLayer>,
 }
 
 impl AIMeta {
@@ -34,6 +57,128 @@ impl AIMeta {
             current_action: None,
             current_action_name: String::default(),
             current_target: None,
+            current_action_reflect: None,
+            current_action_started: None,
+            evaluate_this_frame: true,
+            last_evaluated: None,
+            cooldowns: DecisionCooldowns::default(),
+            current_action_completed: false,
+            layers: HashMap::default(),
         }
     }
+
+    /// Moves the current action of the layer out of the AIMeta, `None` is the default layer.
+    pub(crate) fn take_layer(&mut self, layer: Option<&str>) -> ActionLayer {
+        match layer {
+            None => ActionLayer {
+                current_action: self.current_action.take(),
+                current_action_score: self.current_action_score,
+                current_action_name: std::mem::take(&mut self.current_action_name),
+                current_target: self.current_target.take(),
+                current_action_started: self.current_action_started.take(),
+                current_action_reflect: self.current_action_reflect.take(),
+                current_action_completed: self.current_action_completed,
+            },
+            Some(layer) => self.layers.remove(layer).unwrap_or_default(),
+        }
+    }
+
+    pub(crate) fn set_layer(&mut self, layer: Option<&str>, action_layer: ActionLayer) {
+        match layer {
+            None => {
+                self.current_action = action_layer.current_action;
+                self.current_action_score = action_layer.current_action_score;
+                self.current_action_name = action_layer.current_action_name;
+                self.current_target = action_layer.current_target;
+                self.current_action_started = action_layer.current_action_started;
+                self.current_action_reflect = action_layer.current_action_reflect;
+                self.current_action_completed = action_layer.current_action_completed;
+            }
+            Some(layer) => {
+                self.layers.insert(layer.to_string(), action_layer);
+            }
+        }
+    }
+}
+
+/// The current action of a named action layer, see [`Decision::in_layer`].
+///
+/// [`Decision::in_layer`]: crate::Decision::in_layer
+#[derive(Clone)]
+pub struct ActionLayer {
+    /// The TypeId of the layer's current action.
+    pub current_action: Option<TypeId>,
+    /// The score of the current action
+    pub current_action_score: f32,
+    /// The name of the current action
+    pub current_action_name: String,
+    /// The current target, only exists if the current action is a targeted one.
+    pub current_target: Option<Entity>,
+    /// The elapsed time in seconds at which the current action was started.
+    pub current_action_started: Option<f32>,
+    pub(crate) current_action_reflect: Option<ReflectAction>,
+    pub(crate) current_action_completed: bool,
+}
+
+impl Default for ActionLayer {
+    fn default() -> Self {
+        Self {
+            current_action: None,
+            current_action_score: -1.0,
+            current_action_name: String::default(),
+            current_target: None,
+            current_action_started: None,
+            current_action_reflect: None,
+            current_action_completed: false,
+        }
+    }
+}
+
+/// Tracks the cooldowns of the decisions an entity has stopped performing, see
+/// [`Decision::with_cooldown`].
+///
+/// [`Decision::with_cooldown`]: crate::Decision::with_cooldown
+#[derive(Clone, Debug, Default)]
+pub struct DecisionCooldowns {
+    /// The elapsed time in seconds at which the cooldown of each decision expires. The
+    /// target is only set for decisions with a per target cooldown.
+    pub expires_at: HashMap<(Uuid, Option<Entity>), f32>,
+}
+
+impl DecisionCooldowns {
+    /// Returns the seconds left on the cooldown, if the decision is cooling down.
+    pub fn remaining(
+        &self,
+        decision: Uuid,
+        target: Option<Entity>,
+        elapsed: f32,
+    ) -> Option<f32> {
+        self.expires_at
+            .get(&(decision, target))
+            .map(|expires_at| expires_at - elapsed)
+            .filter(|remaining| *remaining > 0.0)
+    }
+
+    pub fn is_cooling_down(
+        &self,
+        decision: Uuid,
+        target: Option<Entity>,
+        elapsed: f32,
+    ) -> bool {
+        self.remaining(decision, target, elapsed).is_some()
+    }
+
+    pub(crate) fn start(
+        &mut self,
+        decision: Uuid,
+        target: Option<Entity>,
+        expires_at: f32,
+    ) {
+        self.expires_at.insert((decision, target), expires_at);
+    }
+
+    pub(crate) fn remove_expired(&mut self, elapsed: f32) {
+        self.expires_at
+            .retain(|_, expires_at| *expires_at > elapsed);
+    }
 }
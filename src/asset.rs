@@ -0,0 +1,727 @@
+use crate::{
+    considerations::{Consideration, ConsiderationType},
+    decisions::{
+        ConsiderationCompensation, Decision, DecisionAggregation, DecisionBucket,
+        DecisionSelection, Filter,
+    },
+    define_ai::{check_action_layers, DefineUtilityAI},
+    response_curves::ResponseCurve,
+    systems::budget::UpdateBudget,
+    utils, AIDefinitions,
+};
+use bevy::{
+    app::{App, Plugin, PreUpdate},
+    asset::{
+        io::Reader, Asset, AssetApp, AssetEvent, AssetLoader, Assets, AsyncReadExt,
+        LoadContext,
+    },
+    ecs::event::ManualEventReader,
+    log::{debug, error},
+    prelude::{Component, Events, IntoSystemConfigs, Local, Mut, Resource, World},
+    reflect::{GetTypeRegistration, TypePath},
+    utils::HashMap,
+};
+use ron::extensions::Extensions;
+use serde::Deserialize;
+use std::{
+    any::{type_name, TypeId},
+    fmt::{Display, Formatter},
+    time::Duration,
+};
+
+/// Adds support for loading [`AIDefinitionAsset`]s from `.ai.ron` and `.ai.json` files.
+/// Any Marker Components, Actions, Inputs and Filter Components referenced by an asset must
+/// be registered against the App using the [`UtilityAIAssetAppExt`] methods.
+///
+/// Note that a handle to the asset must be kept alive until it has finished loading. When
+/// the asset is modified, e.g. with bevy's `file_watcher` feature, the matching AIDefinition is
+/// replaced while the App is running. Entities keep performing their current action if the
+/// reloaded definition still contains it, see [`DefineUtilityAI::register`].
+pub struct UtilityAIAssetPlugin;
+
+impl Plugin for UtilityAIAssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<AIDefinitionAsset>()
+            .register_asset_loader(AIDefinitionAssetLoader)
+            .init_resource::<AIAssetRegistry>()
+            .add_systems(PreUpdate, register_loaded_ai_definitions);
+    }
+}
+
+/// A serializable description of an AI, the asset equivalent of using [`DefineUtilityAI`].
+#[derive(Asset, TypePath, Deserialize)]
+pub struct AIDefinitionAsset {
+    /// The registered name of the AI's Marker Component.
+    pub marker: String,
+    pub default_intertia: Option<f32>,
+    pub default_compensation: Option<ConsiderationCompensation>,
+    pub selection: Option<DecisionSelection>,
+    pub update_budget: Option<UpdateBudget>,
+    #[serde(default)]
+    pub buckets: Vec<DecisionBucket>,
+    #[serde(default)]
+    pub decisions: Vec<DecisionAsset>,
+}
+
+#[derive(Deserialize)]
+pub struct DecisionAsset {
+    /// The registered name of the Action Component.
+    pub action: String,
+    #[serde(default)]
+    pub targeted: bool,
+    pub name: Option<String>,
+    pub base_score: Option<f32>,
+    pub intertia: Option<f32>,
+    pub compensation: Option<ConsiderationCompensation>,
+    pub aggregation: Option<DecisionAggregation>,
+    pub max_targets: Option<usize>,
+    /// Cooldown in seconds, see [`Decision::with_cooldown`].
+    pub cooldown: Option<f32>,
+    /// Whether the cooldown applies per target, see [`Decision::with_target_cooldown`].
+    #[serde(default)]
+    pub target_cooldown: bool,
+    /// See [`Decision::non_interruptible`].
+    #[serde(default)]
+    pub non_interruptible: bool,
+    /// See [`Decision::as_interrupt`].
+    #[serde(default)]
+    pub interrupt: bool,
+    /// See [`Decision::in_layer`].
+    pub layer: Option<String>,
+    /// The name of a bucket of the AIDefinitionAsset, see [`Decision::in_bucket`].
+    pub bucket: Option<String>,
+    #[serde(default)]
+    pub subject_filters: FiltersAsset,
+    #[serde(default)]
+    pub target_filters: FiltersAsset,
+    #[serde(default)]
+    pub considerations: Vec<ConsiderationAsset>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct FiltersAsset {
+    /// Registered names of Components that must be present.
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Registered names of Components that must not be present.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ConsiderationAsset {
+    /// The registered name of the input system.
+    pub input: String,
+    pub name: Option<String>,
+    pub response_curve: Option<ResponseCurve>,
+    pub bounds: Option<(f32, f32)>,
+    /// The expected (min, max) of the raw input, see [`Consideration::with_input_range`].
+    pub input_range: Option<(f32, f32)>,
+    pub weight: Option<f32>,
+    /// Whether this is the decision's primary consideration, see [`Decision::max_targets`].
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Debug)]
+pub enum AIDefinitionAssetError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+    Json(serde_json::Error),
+    UnknownMarker(String),
+    UnknownAction(String),
+    UnknownInput(String),
+    UnknownFilter(String),
+    Invalid(String),
+}
+
+impl Display for AIDefinitionAssetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AIDefinitionAssetError::Io(err) => write!(f, "could not read asset: {err}"),
+            AIDefinitionAssetError::Ron(err) => write!(f, "invalid RON: {err}"),
+            AIDefinitionAssetError::Json(err) => write!(f, "invalid JSON: {err}"),
+            AIDefinitionAssetError::UnknownMarker(name) => {
+                write!(f, "no Marker Component registered with name '{name}'")
+            }
+            AIDefinitionAssetError::UnknownAction(name) => {
+                write!(f, "no Action registered with name '{name}'")
+            }
+            AIDefinitionAssetError::UnknownInput(name) => {
+                write!(f, "no input system registered with name '{name}'")
+            }
+            AIDefinitionAssetError::UnknownFilter(name) => {
+                write!(f, "no filter Component registered with name '{name}'")
+            }
+            AIDefinitionAssetError::Invalid(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl std::error::Error for AIDefinitionAssetError {}
+
+impl From<std::io::Error> for AIDefinitionAssetError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ron::error::SpannedError> for AIDefinitionAssetError {
+    fn from(value: ron::error::SpannedError) -> Self {
+        Self::Ron(value)
+    }
+}
+
+impl From<serde_json::Error> for AIDefinitionAssetError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+#[derive(Default)]
+pub struct AIDefinitionAssetLoader;
+
+impl AssetLoader for AIDefinitionAssetLoader {
+    type Asset = AIDefinitionAsset;
+    type Settings = ();
+    type Error = AIDefinitionAssetError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let is_json = load_context
+            .path()
+            .extension()
+            .is_some_and(|extension| extension == "json");
+        if is_json {
+            AIDefinitionAsset::from_json(&bytes)
+        } else {
+            AIDefinitionAsset::from_ron(&bytes)
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ai.ron", "ai.json"]
+    }
+}
+
+type RegisterMarkerFn = fn(
+    &mut World,
+    &AIDefinitionAsset,
+    Vec<Decision>,
+) -> Result<(), AIDefinitionAssetError>;
+type ConstructConsiderationFn = Box<dyn Fn() -> Consideration + Send + Sync>;
+
+/// Stores everything that can be referenced by name from an [`AIDefinitionAsset`].
+#[derive(Resource, Default)]
+pub(crate) struct AIAssetRegistry {
+    markers: HashMap<String, RegisterMarkerFn>,
+    actions: HashMap<String, fn(bool) -> Decision>,
+    filters: HashMap<String, TypeId>,
+    inputs: HashMap<String, ConstructConsiderationFn>,
+}
+
+/// Methods for registering the types and systems referenced by [`AIDefinitionAsset`]s,
+/// these are referenced in the asset by their type or function name.
+pub trait UtilityAIAssetAppExt {
+    fn register_ai_marker<T: Component>(&mut self) -> &mut Self;
+
+    fn register_ai_action<C: Component + GetTypeRegistration>(&mut self) -> &mut Self;
+
+    fn register_ai_filter<C: Component>(&mut self) -> &mut Self;
+
+    fn register_ai_input<M, S>(&mut self, input: S) -> &mut Self
+    where
+        S: IntoSystemConfigs<M> + Copy + Send + Sync + 'static;
+
+    fn register_ai_targeted_input<M, S>(&mut self, input: S) -> &mut Self
+    where
+        S: IntoSystemConfigs<M> + Copy + Send + Sync + 'static;
+}
+
+impl UtilityAIAssetAppExt for App {
+    fn register_ai_marker<T: Component>(&mut self) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(AIAssetRegistry::default)
+            .markers
+            .insert(
+                utils::trim_type_name(type_name::<T>()).into(),
+                register_marker::<T>,
+            );
+        self
+    }
+
+    fn register_ai_action<C: Component + GetTypeRegistration>(&mut self) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(AIAssetRegistry::default)
+            .actions
+            .insert(
+                utils::trim_type_name(type_name::<C>()).into(),
+                construct_decision::<C>,
+            );
+        self
+    }
+
+    fn register_ai_filter<C: Component>(&mut self) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(AIAssetRegistry::default)
+            .filters
+            .insert(
+                utils::trim_type_name(type_name::<C>()).into(),
+                TypeId::of::<C>(),
+            );
+        self
+    }
+
+    fn register_ai_input<M, S>(&mut self, input: S) -> &mut Self
+    where
+        S: IntoSystemConfigs<M> + Copy + Send + Sync + 'static,
+    {
+        self.world_mut()
+            .get_resource_or_insert_with(AIAssetRegistry::default)
+            .inputs
+            .insert(
+                utils::trim_type_name(utils::type_name_of(&input)).into(),
+                Box::new(move || Consideration::simple(input)),
+            );
+        self
+    }
+
+    fn register_ai_targeted_input<M, S>(&mut self, input: S) -> &mut Self
+    where
+        S: IntoSystemConfigs<M> + Copy + Send + Sync + 'static,
+    {
+        self.world_mut()
+            .get_resource_or_insert_with(AIAssetRegistry::default)
+            .inputs
+            .insert(
+                utils::trim_type_name(utils::type_name_of(&input)).into(),
+                Box::new(move || Consideration::targeted(input)),
+            );
+        self
+    }
+}
+
+fn register_marker<T: Component>(
+    world: &mut World,
+    asset: &AIDefinitionAsset,
+    decisions: Vec<Decision>,
+) -> Result<(), AIDefinitionAssetError> {
+    // checked up front, as registering panics
+    check_action_layers(
+        world.resource::<AIDefinitions>(),
+        TypeId::of::<T>(),
+        &decisions,
+    )
+    .map_err(AIDefinitionAssetError::Invalid)?;
+
+    let mut define_ai = DefineUtilityAI::<T>::new();
+    if let Some(default_intertia) = asset.default_intertia {
+        define_ai = define_ai.set_default_intertia(default_intertia);
+    }
+    if let Some(default_compensation) = asset.default_compensation {
+        define_ai = define_ai.set_default_compensation(default_compensation);
+    }
+    if let Some(selection) = asset.selection {
+        define_ai = define_ai.set_selection(selection);
+    }
+    if let Some(update_budget) = asset.update_budget {
+        define_ai = define_ai.set_update_budget(update_budget);
+    }
+    for bucket in &asset.buckets {
+        define_ai = define_ai.add_bucket(&bucket.name, bucket.priority, bucket.threshold);
+    }
+    for decision in decisions {
+        define_ai = define_ai.add_decision(decision);
+    }
+    define_ai.register_in_world(world);
+    Ok(())
+}
+
+fn construct_decision<C: Component + GetTypeRegistration>(is_targeted: bool) -> Decision {
+    match is_targeted {
+        true => Decision::targeted::<C>(),
+        false => Decision::simple::<C>(),
+    }
+}
+
+impl AIDefinitionAsset {
+    /// Parses the asset from RON, optional fields do not need to be wrapped in `Some`.
+    pub fn from_ron(bytes: &[u8]) -> Result<Self, AIDefinitionAssetError> {
+        let options =
+            ron::Options::default().with_default_extension(Extensions::IMPLICIT_SOME);
+        Ok(options.from_bytes(bytes)?)
+    }
+
+    pub fn from_json(bytes: &[u8]) -> Result<Self, AIDefinitionAssetError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    /// Resolves the asset into an AIDefinition and registers it in the AIDefinitions
+    /// resource.
+    pub fn register(&self, world: &mut World) -> Result<(), AIDefinitionAssetError> {
+        world.init_resource::<AIAssetRegistry>();
+        world.resource_scope(|world, registry: Mut<AIAssetRegistry>| {
+            let register_marker =
+                registry.markers.get(&self.marker).ok_or_else(|| {
+                    AIDefinitionAssetError::UnknownMarker(self.marker.clone())
+                })?;
+
+            if let Some(default_intertia) = self.default_intertia {
+                check_intertia(default_intertia)?;
+            }
+            if let Some(selection) = self.selection {
+                check_selection(selection)?;
+            }
+            if let Some(update_budget) = self.update_budget {
+                check_update_budget(update_budget)?;
+            }
+            check_buckets(self)?;
+
+            let decisions = self
+                .decisions
+                .iter()
+                .map(|decision| decision.resolve(&registry))
+                .collect::<Result<Vec<Decision>, AIDefinitionAssetError>>()?;
+
+            register_marker(world, self, decisions)
+        })
+    }
+}
+
+impl DecisionAsset {
+    fn resolve(
+        &self,
+        registry: &AIAssetRegistry,
+    ) -> Result<Decision, AIDefinitionAssetError> {
+        let construct = registry
+            .actions
+            .get(&self.action)
+            .ok_or_else(|| AIDefinitionAssetError::UnknownAction(self.action.clone()))?;
+        let mut decision = construct(self.targeted);
+
+        if let Some(name) = &self.name {
+            decision = decision.with_name(name);
+        }
+        if let Some(base_score) = self.base_score {
+            if base_score <= 0.0 || base_score >= 10.0 {
+                return Err(AIDefinitionAssetError::Invalid(format!(
+                    "base_score of decision '{}' must be between 0.0 and 10.0",
+                    decision.name
+                )));
+            }
+            decision = decision.set_base_score(base_score);
+        }
+        if let Some(intertia) = self.intertia {
+            check_intertia(intertia)?;
+            decision = decision.set_intertia(intertia);
+        }
+        if let Some(compensation) = self.compensation {
+            decision = decision.set_compensation(compensation);
+        }
+        if let Some(aggregation) = &self.aggregation {
+            decision = decision.set_aggregation(aggregation.clone());
+        }
+        if !self.targeted && !self.target_filters.is_empty() {
+            return Err(AIDefinitionAssetError::Invalid(format!(
+                "only targeted decisions may have target filters, see decision '{}'",
+                decision.name
+            )));
+        }
+
+        decision
+            .subject_filters
+            .extend(self.subject_filters.resolve(registry)?);
+        decision
+            .target_filters
+            .extend(self.target_filters.resolve(registry)?);
+
+        for consideration_asset in &self.considerations {
+            let consideration = consideration_asset.resolve(registry)?;
+            if !self.targeted
+                && consideration.consideration_type == ConsiderationType::Targeted
+            {
+                return Err(AIDefinitionAssetError::Invalid(format!(
+                    "cannot add targeted consideration '{}' to simple decision '{}'",
+                    consideration.name, decision.name
+                )));
+            }
+            if consideration_asset.primary {
+                if consideration.consideration_type != ConsiderationType::Targeted
+                    || decision.primary_consideration.is_some()
+                {
+                    return Err(AIDefinitionAssetError::Invalid(format!(
+                        "decision '{}' must have at most one targeted primary consideration",
+                        decision.name
+                    )));
+                }
+                decision = decision.add_primary_consideration(consideration);
+            } else {
+                decision = decision.add_consideration(consideration);
+            }
+        }
+        if let Some(max_targets) = self.max_targets {
+            if !self.targeted || max_targets == 0 {
+                return Err(AIDefinitionAssetError::Invalid(format!(
+                    "max_targets of decision '{}' must be at least 1 on a targeted decision",
+                    decision.name
+                )));
+            }
+            decision = decision.max_targets(max_targets);
+        }
+        if let Some(cooldown) = self.cooldown {
+            // this also rejects cooldowns too large for a Duration
+            let cooldown = Duration::try_from_secs_f32(cooldown)
+                .ok()
+                .filter(|cooldown| !cooldown.is_zero())
+                .ok_or_else(|| {
+                    AIDefinitionAssetError::Invalid(format!(
+                        "cooldown of decision '{}' must be a positive number of seconds",
+                        decision.name
+                    ))
+                })?;
+            decision = if self.target_cooldown {
+                if !self.targeted {
+                    return Err(AIDefinitionAssetError::Invalid(format!(
+                        "target_cooldown of decision '{}' requires a targeted decision",
+                        decision.name
+                    )));
+                }
+                decision.with_target_cooldown(cooldown)
+            } else {
+                decision.with_cooldown(cooldown)
+            };
+        }
+        if self.non_interruptible {
+            decision = decision.non_interruptible();
+        }
+        if self.interrupt {
+            decision = decision.as_interrupt();
+        }
+        if let Some(layer) = &self.layer {
+            decision = decision.in_layer(layer);
+        }
+        if let Some(bucket) = &self.bucket {
+            decision = decision.in_bucket(bucket);
+        }
+
+        Ok(decision)
+    }
+}
+
+impl FiltersAsset {
+    fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    fn resolve(
+        &self,
+        registry: &AIAssetRegistry,
+    ) -> Result<Vec<Filter>, AIDefinitionAssetError> {
+        let lookup = |name: &String| {
+            registry
+                .filters
+                .get(name)
+                .copied()
+                .ok_or_else(|| AIDefinitionAssetError::UnknownFilter(name.clone()))
+        };
+        let mut filters = Vec::new();
+        for name in &self.include {
+            filters.push(Filter::Inclusive(lookup(name)?));
+        }
+        for name in &self.exclude {
+            filters.push(Filter::Exclusive(lookup(name)?));
+        }
+        Ok(filters)
+    }
+}
+
+impl ConsiderationAsset {
+    fn resolve(
+        &self,
+        registry: &AIAssetRegistry,
+    ) -> Result<Consideration, AIDefinitionAssetError> {
+        let construct = registry
+            .inputs
+            .get(&self.input)
+            .ok_or_else(|| AIDefinitionAssetError::UnknownInput(self.input.clone()))?;
+        let mut consideration = construct();
+
+        if let Some(response_curve) = &self.response_curve {
+            response_curve.validate().map_err(|reason| {
+                AIDefinitionAssetError::Invalid(format!(
+                    "invalid response_curve for consideration '{}': {reason}",
+                    consideration.name
+                ))
+            })?;
+            consideration = consideration.with_response_curve(response_curve.clone());
+        }
+        if let Some((min, max)) = self.input_range {
+            check_input_range(min, max, &consideration.name)?;
+            consideration = consideration.with_input_range(min, max);
+        }
+        if let Some((lower, upper)) = self.bounds {
+            check_bounds(lower, upper, &consideration.name)?;
+            consideration = consideration.with_bounds(lower, upper);
+        }
+        if let Some(weight) = self.weight {
+            check_weight(weight, &consideration.name)?;
+            consideration = consideration.with_weight(weight);
+        }
+        if let Some(name) = &self.name {
+            consideration = consideration.with_name(name);
+        }
+
+        Ok(consideration)
+    }
+}
+
+fn check_selection(selection: DecisionSelection) -> Result<(), AIDefinitionAssetError> {
+    let valid = match selection {
+        DecisionSelection::Highest => true,
+        DecisionSelection::TopN(n) => n > 0,
+        DecisionSelection::WithinPercent(percent) => (0.0..=1.0).contains(&percent),
+        DecisionSelection::Softmax { temperature } => temperature > 0.0,
+    };
+    if !valid {
+        return Err(AIDefinitionAssetError::Invalid(format!(
+            "invalid selection {:?}",
+            selection
+        )));
+    }
+    Ok(())
+}
+
+fn check_update_budget(
+    update_budget: UpdateBudget,
+) -> Result<(), AIDefinitionAssetError> {
+    let valid = match update_budget {
+        UpdateBudget::EveryFrame => true,
+        UpdateBudget::MaxEntitiesPerFrame(max_entities) => max_entities > 0,
+        UpdateBudget::Interval(interval) => interval.is_finite() && interval >= 0.0,
+    };
+    if !valid {
+        return Err(AIDefinitionAssetError::Invalid(format!(
+            "invalid update_budget {:?}",
+            update_budget
+        )));
+    }
+    Ok(())
+}
+
+fn check_input_range(
+    min: f32,
+    max: f32,
+    consideration: &str,
+) -> Result<(), AIDefinitionAssetError> {
+    if !min.is_finite() || !max.is_finite() || min >= max {
+        return Err(AIDefinitionAssetError::Invalid(format!(
+            "invalid input_range for consideration '{}', expected finite min < max",
+            consideration
+        )));
+    }
+    Ok(())
+}
+
+fn check_bounds(
+    lower: f32,
+    upper: f32,
+    consideration: &str,
+) -> Result<(), AIDefinitionAssetError> {
+    if lower.is_nan() || upper.is_nan() || lower < 0.0 || lower >= upper {
+        return Err(AIDefinitionAssetError::Invalid(format!(
+            "invalid bounds for consideration '{}', expected 0.0 <= lower < upper",
+            consideration
+        )));
+    }
+    Ok(())
+}
+
+fn check_weight(weight: f32, consideration: &str) -> Result<(), AIDefinitionAssetError> {
+    if !weight.is_finite() || weight < 0.0 {
+        return Err(AIDefinitionAssetError::Invalid(format!(
+            "weight of consideration '{}' must be a finite number >= 0.0",
+            consideration
+        )));
+    }
+    Ok(())
+}
+
+fn check_buckets(asset: &AIDefinitionAsset) -> Result<(), AIDefinitionAssetError> {
+    for (idx, bucket) in asset.buckets.iter().enumerate() {
+        if bucket.threshold.is_nan() || bucket.threshold < 0.0 {
+            return Err(AIDefinitionAssetError::Invalid(format!(
+                "threshold of bucket '{}' must be at least 0.0",
+                bucket.name
+            )));
+        }
+        if asset.buckets[..idx].iter().any(|b| b.name == bucket.name) {
+            return Err(AIDefinitionAssetError::Invalid(format!(
+                "bucket '{}' is defined more than once",
+                bucket.name
+            )));
+        }
+    }
+    for decision in &asset.decisions {
+        if let Some(bucket) = &decision.bucket {
+            if !asset.buckets.iter().any(|b| &b.name == bucket) {
+                return Err(AIDefinitionAssetError::Invalid(format!(
+                    "decision for action '{}' is in unknown bucket '{}'",
+                    decision.action, bucket
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_intertia(intertia: f32) -> Result<(), AIDefinitionAssetError> {
+    if !(0.0..1.0).contains(&intertia) {
+        return Err(AIDefinitionAssetError::Invalid(
+            "intertia must be between 0.0 and 1.0".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn register_loaded_ai_definitions(
+    world: &mut World,
+    mut reader: Local<ManualEventReader<AssetEvent<AIDefinitionAsset>>>,
+) {
+    // an asset can be both loaded and modified in the same frame, it only needs to be
+    // registered once
+    let mut loaded = Vec::new();
+    for event in reader.read(world.resource::<Events<AssetEvent<AIDefinitionAsset>>>()) {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } =
+            event
+        {
+            if !loaded.contains(id) {
+                loaded.push(*id);
+            }
+        }
+    }
+
+    if loaded.is_empty() {
+        return;
+    }
+
+    world.resource_scope(|world, assets: Mut<Assets<AIDefinitionAsset>>| {
+        for id in loaded {
+            let Some(asset) = assets.get(id) else {
+                continue;
+            };
+            match asset.register(world) {
+                Ok(_) => debug!("Registered AI definition for '{}'", asset.marker),
+                Err(err) => error!(
+                    "Failed to register AI definition for '{}': {}",
+                    asset.marker, err
+                ),
+            }
+        }
+    });
+}
@@ -29,16 +29,49 @@ pub struct Consideration {
     /// The upper bound for the calculated score, defaults to 1.0. Must be >= 0.0 &
     /// greater than the lower_bound.
     pub upper_bound: f32,
+    /// The expected (min, max) of the raw input, if set the input is remapped onto
+    /// 0.0..=1.0 before the response curve is applied.
+    pub input_range: Option<(f32, f32)>,
+    /// The weight of this consideration when aggregating the decision's score, defaults
+    /// to 1.0. See [`DecisionAggregation`](crate::decisions::DecisionAggregation).
+    pub weight: f32,
     pub(crate) system_app_config: Option<SystemConfigs>,
 }
 
 impl Consideration {
     pub fn calculate_score(&self, input_score: f32) -> f32 {
-        self.response_curve
-            .transform(input_score)
+        self.calculate_score_with(&self.response_curve, input_score)
+    }
+
+    /// Calculates the score using the given response curve instead of this consideration's.
+    pub(crate) fn calculate_score_with(
+        &self,
+        response_curve: &ResponseCurve,
+        input_score: f32,
+    ) -> f32 {
+        response_curve
+            .transform(self.normalize_input(input_score))
             .clamp(self.lower_bound, self.upper_bound)
     }
 
+    /// Remaps the raw input score onto 0.0..=1.0 using the input range, if one is set.
+    pub fn normalize_input(&self, input_score: f32) -> f32 {
+        match self.input_range {
+            Some((min, max)) => ((input_score - min) / (max - min)).clamp(0.0, 1.0),
+            None => input_score,
+        }
+    }
+
+    fn default_name(&self) -> String {
+        match self.input_range {
+            Some((min, max)) => format!(
+                "{} [{:.}..{:.}] - {}",
+                self.input_name, min, max, self.response_curve
+            ),
+            None => format!("{} - {}", self.input_name, self.response_curve),
+        }
+    }
+
     fn construct(
         input_name: String,
         input: TypeId,
@@ -56,6 +89,8 @@ impl Consideration {
             response_curve: ResponseCurve::LinearCurve(Linear::new(1.0)),
             lower_bound: 0.0,
             upper_bound: 1.0,
+            input_range: None,
+            weight: 1.0,
         }
     }
 
@@ -78,12 +113,30 @@ impl Consideration {
     }
 
     pub fn with_response_curve(self, response_curve: impl Into<ResponseCurve>) -> Self {
-        let response_curve = response_curve.into();
-        Self {
-            name: format!("{} - {}", self.input_name, response_curve),
-            response_curve,
+        let mut consideration = Self {
+            response_curve: response_curve.into(),
+            ..self
+        };
+        consideration.name = consideration.default_name();
+        consideration
+    }
+
+    /// Sets the expected range of the raw input, which is then remapped onto 0.0..=1.0
+    /// before the response curve is applied. Inputs outside of the range are clamped.
+    /// A name set with [`Consideration::with_name`] is kept.
+    pub fn with_input_range(self, min: f32, max: f32) -> Self {
+        if min >= max {
+            panic!("The input range's min must be less than its max");
+        }
+        let has_default_name = self.name == self.default_name();
+        let mut consideration = Self {
+            input_range: Some((min, max)),
             ..self
+        };
+        if has_default_name {
+            consideration.name = consideration.default_name();
         }
+        consideration
     }
 
     /// Sets the lower & upper bounds, by default these are 0.0 and 1.0.
@@ -101,6 +154,14 @@ impl Consideration {
         }
     }
 
+    /// Sets the weight of this consideration, by default this is 1.0. Must be >= 0.0.
+    pub fn with_weight(self, weight: f32) -> Self {
+        if weight < 0.0 {
+            panic!("Consideration's weight must be >= 0.0");
+        }
+        Self { weight, ..self }
+    }
+
     pub fn with_name(self, name: impl Into<String>) -> Self {
         Self {
             name: name.into(),
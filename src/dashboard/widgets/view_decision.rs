@@ -3,9 +3,11 @@ use std::collections::VecDeque;
 use crate::dashboard::data::DashboardData;
 use crate::dashboard::view::DashboardState;
 use crate::response_curves::InputTransform;
-use crate::AIDefinitions;
-use bevy::ecs::system::{Local, Res, SystemParam, SystemState};
+use crate::{AIDefinitions, AIMeta};
+use bevy::ecs::entity::Entity;
+use bevy::ecs::system::{Local, Query, Res, SystemParam, SystemState};
 use bevy::ecs::world::World;
+use bevy::time::Time;
 use bevy_egui::egui::{self, Ui};
 use egui_plot::{Bar, BarChart, Line, Plot, PlotBounds, PlotPoints};
 
@@ -16,6 +18,8 @@ pub(crate) struct DecisionView<'w, 's> {
     ai_definitions: Res<'w, AIDefinitions>,
     dashboard_state: Res<'w, DashboardState>,
     dashboard_data: Res<'w, DashboardData>,
+    time: Res<'w, Time>,
+    q_ai_meta: Query<'w, 's, (Entity, &'static AIMeta)>,
     decision_idx: Local<'s, usize>,
     consideration_idx: Local<'s, usize>,
 }
@@ -34,6 +38,8 @@ impl<'w, 's> WidgetSystem for DecisionView<'w, 's> {
             ai_definitions,
             dashboard_state,
             dashboard_data,
+            time,
+            q_ai_meta,
             mut decision_idx,
             mut consideration_idx,
         } = state.get_mut(world);
@@ -45,13 +51,18 @@ impl<'w, 's> WidgetSystem for DecisionView<'w, 's> {
         let selected_ai_definition =
             dashboard_state.selected_ai_definition.as_ref().unwrap().id;
 
-        let decisions = &ai_definitions
-            .map
-            .get(&selected_ai_definition)
-            .unwrap()
-            .decisions;
+        // the definition may have been unregistered
+        let Some(ai_definition) = ai_definitions.map.get(&selected_ai_definition) else {
+            return;
+        };
+        let decisions = &ai_definition.decisions;
 
-        if *decision_idx > decisions.len() - 1 {
+        if decisions.is_empty() {
+            return;
+        }
+
+        // the definition may have been replaced with one with fewer decisions
+        if *decision_idx >= decisions.len() {
             *decision_idx = 0;
         }
 
@@ -74,6 +85,47 @@ impl<'w, 's> WidgetSystem for DecisionView<'w, 's> {
 
         ui.label(format!("base score: {}", decision.base_score));
 
+        if let Some(bucket) = &decision.bucket {
+            ui.label(format!("bucket: {}", bucket));
+        }
+
+        if let Some(cooldown) = decision.cooldown {
+            let per_target = if decision.cooldown_per_target {
+                " per target"
+            } else {
+                ""
+            };
+            ui.label(format!(
+                "cooldown: {:.1}s{}",
+                cooldown.as_secs_f32(),
+                per_target
+            ));
+
+            let elapsed = time.elapsed_seconds();
+            for (entity, ai_meta) in
+                q_ai_meta.iter_many(&dashboard_state.selected_entities)
+            {
+                for (_, target) in ai_meta
+                    .cooldowns
+                    .expires_at
+                    .keys()
+                    .filter(|(decision_id, _)| *decision_id == decision.id)
+                {
+                    if let Some(remaining) =
+                        ai_meta.cooldowns.remaining(decision.id, *target, elapsed)
+                    {
+                        let target = target
+                            .map(|target| format!(" on {:?}", target))
+                            .unwrap_or_default();
+                        ui.label(format!(
+                            "{:?}{}: {:.1}s remaining",
+                            entity, target, remaining
+                        ));
+                    }
+                }
+            }
+        }
+
         if decision.considerations.is_empty() {
             return;
         }
@@ -92,6 +144,10 @@ impl<'w, 's> WidgetSystem for DecisionView<'w, 's> {
             }
         });
 
+        if *consideration_idx >= decision.considerations.len() {
+            *consideration_idx = 0;
+        }
+
         let consideration = &decision.considerations[*consideration_idx];
 
         let plot = Plot::new(format!(
@@ -102,7 +158,7 @@ impl<'w, 's> WidgetSystem for DecisionView<'w, 's> {
         .allow_scroll(false)
         .allow_zoom(false);
 
-        let response_curve = consideration.response_curve.clone();
+        let input_range = consideration.input_range;
 
         let mut input_values: Vec<f32> = Vec::from_iter(
             dashboard_data
@@ -112,28 +168,44 @@ impl<'w, 's> WidgetSystem for DecisionView<'w, 's> {
                 .clone(),
         );
 
-        if input_values.is_empty() {
-            return;
-        }
-
         input_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
 
-        let mut x_l = input_values[input_values.len() / 10] as f64;
-        let mut x_u = input_values[(input_values.len() * 9) / 10] as f64;
+        // plot over the declared input range if there is one, otherwise guess a sensible
+        // domain from the observed input values
+        let (x_l, x_u) = match input_range {
+            Some((min, max)) => (min as f64, max as f64),
+            None => {
+                if input_values.is_empty() {
+                    return;
+                }
 
-        let base_unit = 5.0 * 10.0f64.powf(((x_u + x_l) / 2.0).log10().floor());
+                let mut x_l = input_values[input_values.len() / 10] as f64;
+                let mut x_u = input_values[(input_values.len() * 9) / 10] as f64;
 
-        x_l = (x_l / base_unit).floor() * base_unit;
-        x_u = (x_u / base_unit).ceil() * base_unit;
+                let base_unit = 5.0 * 10.0f64.powf(((x_u + x_l) / 2.0).log10().floor());
 
-        let points = PlotPoints::from_explicit_callback(
-            move |x| (response_curve.transform(x as f32) as f64).clamp(0.0, 1.0),
-            x_l..=x_u,
-            50,
-        );
+                x_l = (x_l / base_unit).floor() * base_unit;
+                x_u = (x_u / base_unit).ceil() * base_unit;
+                (x_l, x_u)
+            }
+        };
+
+        let points: PlotPoints = (0..=50)
+            .map(|i| {
+                let x = x_l + (x_u - x_l) * i as f64 / 50.0;
+                let y = consideration
+                    .response_curve
+                    .transform(consideration.normalize_input(x as f32));
+                [x, (y as f64).clamp(0.0, 1.0)]
+            })
+            .collect();
 
         // calculate histogram
-        let histogram = generate_histogram(&input_values);
+        let histogram = if input_values.is_empty() {
+            None
+        } else {
+            generate_histogram(&input_values)
+        };
 
         plot.show(ui, |plot_ui| {
             plot_ui.set_plot_bounds(PlotBounds::from_min_max([x_l, 0.0], [x_u, 1.01]));
@@ -1,13 +1,21 @@
 use crate::{
     considerations::{Consideration, ConsiderationType},
+    systems::update_action::{
+        trigger_action_ended, trigger_action_started, ReflectAction,
+        ReflectActionComponent,
+    },
     utils::trim_type_name,
 };
 use bevy::{
     prelude::Component,
-    reflect::{GetTypeRegistration, TypeRegistration},
+    reflect::{GetTypeRegistration, Reflect, TypeRegistration},
 };
 use rand::{distributions::Alphanumeric, Rng};
-use std::any::{type_name, TypeId};
+use std::{
+    any::{type_name, TypeId},
+    sync::Arc,
+    time::Duration,
+};
 use uuid::Uuid;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -27,18 +35,237 @@ impl Filter {
     }
 }
 
+/// Multiplying consideration scores together punishes decisions with many considerations,
+/// compensation corrects for this so that decisions remain comparable regardless of how
+/// many considerations they have. This only applies to [`DecisionAggregation::Product`].
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
+pub enum ConsiderationCompensation {
+    /// Consideration scores are multiplied as is.
+    #[default]
+    None,
+    /// Each consideration score is raised towards 1.0 by a 'makeup value' proportional to
+    /// the number of considerations, i.e. `score + (1 - score) * (1 - 1 / n) * score`.
+    MakeupValue,
+    /// The product of the consideration scores is normalised by taking its n-th root.
+    GeometricMean,
+}
+
+impl ConsiderationCompensation {
+    /// Adjusts a single consideration's score, given the number of considerations.
+    pub(crate) fn compensate_score(&self, score: f32, count: usize) -> f32 {
+        match self {
+            ConsiderationCompensation::MakeupValue if count > 0 => {
+                let modification_factor = 1.0 - 1.0 / count as f32;
+                let makeup_value = (1.0 - score) * modification_factor;
+                score + makeup_value * score
+            }
+            _ => score,
+        }
+    }
+
+    /// Adjusts the product of all consideration scores, given the number of considerations.
+    pub(crate) fn compensate_product(&self, product: f32, count: usize) -> f32 {
+        match self {
+            ConsiderationCompensation::GeometricMean if count > 0 => {
+                product.powf(1.0 / count as f32)
+            }
+            _ => product,
+        }
+    }
+}
+
+/// How a decision's consideration scores are combined into a single score, which is then
+/// multiplied by the decision's base score.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
+pub enum DecisionAggregation {
+    /// The product of the consideration scores, each raised to the power of its weight.
+    #[default]
+    Product,
+    /// The sum of the consideration scores multiplied by their weights, divided by the
+    /// total weight so that the result stays between 0.0 and 1.0.
+    WeightedSum,
+    /// The lowest consideration score, weights are ignored.
+    Minimum,
+    /// A user defined fold over the consideration scores, see [`DecisionAggregation::fold`].
+    #[cfg_attr(feature = "assets", serde(skip_deserializing))]
+    Fold(AggregationFold),
+}
+
+impl DecisionAggregation {
+    /// Creates an aggregation which folds over the consideration scores starting from the
+    /// initial value, the closure is called with the accumulator, score & weight.
+    pub fn fold(
+        init: f32,
+        f: impl Fn(f32, f32, f32) -> f32 + Send + Sync + 'static,
+    ) -> Self {
+        Self::Fold(AggregationFold {
+            init,
+            f: Arc::new(f),
+        })
+    }
+
+    /// Aggregates the (score, weight) pairs of a decision's considerations. Compensation is
+    /// based on the number of scores, as considerations whose inputs haven't been
+    /// calculated are left out.
+    pub(crate) fn aggregate(
+        &self,
+        scores: &[(f32, f32)],
+        compensation: ConsiderationCompensation,
+    ) -> f32 {
+        let consideration_count = scores.len();
+        match self {
+            DecisionAggregation::Product => {
+                let product = scores.iter().fold(1.0, |acc, (score, weight)| {
+                    acc * compensation
+                        .compensate_score(*score, consideration_count)
+                        .powf(*weight)
+                });
+                compensation.compensate_product(product, consideration_count)
+            }
+            DecisionAggregation::WeightedSum => {
+                let total_weight: f32 = scores.iter().map(|(_, weight)| weight).sum();
+                if total_weight == 0.0 {
+                    return 1.0;
+                }
+                scores
+                    .iter()
+                    .map(|(score, weight)| score * weight)
+                    .sum::<f32>()
+                    / total_weight
+            }
+            DecisionAggregation::Minimum => scores
+                .iter()
+                .map(|(score, _)| *score)
+                .reduce(f32::min)
+                .unwrap_or(1.0),
+            DecisionAggregation::Fold(fold) => {
+                scores.iter().fold(fold.init, |acc, (score, weight)| {
+                    (fold.f)(acc, *score, *weight)
+                })
+            }
+        }
+    }
+}
+
+/// A user defined aggregation, see [`DecisionAggregation::fold`].
+#[derive(Clone)]
+pub struct AggregationFold {
+    pub init: f32,
+    f: Arc<dyn Fn(f32, f32, f32) -> f32 + Send + Sync>,
+}
+
+/// How an AI picks which of its evaluated decisions to act on. The random strategies pick
+/// from their candidates with a probability proportional to the candidate's score, using
+/// the [`UtilityAIRng`](crate::plugin::UtilityAIRng) resource.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
+pub enum DecisionSelection {
+    /// Always pick the highest scoring decision.
+    #[default]
+    Highest,
+    /// Pick randomly from the N highest scoring decisions.
+    TopN(usize),
+    /// Pick randomly from the decisions whose score is within the given fraction of the
+    /// highest score, e.g. 0.1 picks from decisions scoring at least 90% of the best.
+    WithinPercent(f32),
+    /// Pick randomly from all decisions, weighted by `e ^ (score / temperature)`. Lower
+    /// temperatures favour the highest scoring decisions more strongly.
+    Softmax { temperature: f32 },
+}
+
+impl DecisionSelection {
+    /// Returns the index of the selected score, the scores must be sorted in descending
+    /// order and must not be empty.
+    pub(crate) fn select(&self, sorted_scores: &[f32], rng: &mut impl Rng) -> usize {
+        let best = sorted_scores[0];
+        match self {
+            DecisionSelection::Highest => 0,
+            DecisionSelection::TopN(n) => {
+                let candidates = &sorted_scores[..(*n).clamp(1, sorted_scores.len())];
+                weighted_choice(candidates.iter().copied(), rng)
+            }
+            DecisionSelection::WithinPercent(percent) => {
+                let threshold = best * (1.0 - percent);
+                let candidates = sorted_scores.iter().take_while(|s| **s >= threshold);
+                weighted_choice(candidates.copied(), rng)
+            }
+            DecisionSelection::Softmax { temperature } => {
+                // subtract the best score for numerical stability
+                let weights = sorted_scores
+                    .iter()
+                    .map(|score| ((score - best) / temperature).exp());
+                weighted_choice(weights, rng)
+            }
+        }
+    }
+}
+
+/// Picks an index with a probability proportional to its weight, falling back to the first
+/// index if there are no positive weights.
+fn weighted_choice(
+    weights: impl Iterator<Item = f32> + Clone,
+    rng: &mut impl Rng,
+) -> usize {
+    let total: f32 = weights.clone().map(|w| w.max(0.0)).sum();
+    if total <= 0.0 || !total.is_finite() {
+        return 0;
+    }
+    let mut choice = rng.gen_range(0.0..total);
+    let mut last = 0;
+    for (idx, weight) in weights.enumerate() {
+        let weight = weight.max(0.0);
+        if choice < weight {
+            return idx;
+        }
+        choice -= weight;
+        if weight > 0.0 {
+            last = idx;
+        }
+    }
+    // only reachable through floating point error
+    last
+}
+
+/// A prioritised group of decisions. The decisions of the highest priority bucket with a
+/// decision scoring above its threshold are picked from, regardless of the scores in lower
+/// priority buckets.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
+pub struct DecisionBucket {
+    pub name: String,
+    pub priority: i32,
+    pub threshold: f32,
+}
+
 pub struct Decision {
     pub(crate) id: Uuid,
     pub(crate) name: String,
     pub(crate) action_name: String,
     pub(crate) action: TypeId,
-    pub(crate) type_registration: TypeRegistration,
+    /// The type registrations of the action and any extra components.
+    pub(crate) type_registrations: Vec<TypeRegistration>,
+    /// The reflection data of the action, only `None` for delegating decisions.
+    pub(crate) reflect_action: Option<ReflectAction>,
+    /// The Marker Component of the AIDefinition this decision delegates to.
+    pub(crate) delegate: Option<TypeId>,
     pub(crate) is_targeted: bool,
     pub(crate) considerations: Vec<Consideration>,
     pub(crate) base_score: f32,
     pub(crate) subject_filters: Vec<Filter>,
     pub(crate) target_filters: Vec<Filter>,
     pub(crate) intertia: Option<f32>,
+    pub(crate) compensation: Option<ConsiderationCompensation>,
+    pub(crate) aggregation: DecisionAggregation,
+    pub(crate) max_targets: Option<usize>,
+    pub(crate) primary_consideration: Option<usize>,
+    pub(crate) cooldown: Option<Duration>,
+    pub(crate) cooldown_per_target: bool,
+    pub(crate) non_interruptible: bool,
+    pub(crate) is_interrupt: bool,
+    pub(crate) layer: Option<String>,
+    pub(crate) bucket: Option<String>,
 }
 
 fn gen_random_tag() -> String {
@@ -50,31 +277,121 @@ fn gen_random_tag() -> String {
 }
 
 impl Decision {
-    fn construct<C: Component + GetTypeRegistration>(is_targeted: bool) -> Self {
-        let action_name: String = trim_type_name(type_name::<C>()).into();
+    fn construct<C: Component + GetTypeRegistration>(
+        is_targeted: bool,
+        prototype: Option<Arc<dyn Reflect>>,
+    ) -> Self {
+        let reflect_action = ReflectAction {
+            component: ReflectActionComponent::of::<C>(prototype),
+            extra_components: Vec::new(),
+            trigger_started: trigger_action_started::<C>,
+            trigger_ended: trigger_action_ended::<C>,
+        };
+        Self {
+            type_registrations: vec![C::get_type_registration()],
+            reflect_action: Some(reflect_action),
+            ..Decision::base(type_name::<C>(), TypeId::of::<C>(), is_targeted)
+        }
+    }
+
+    fn base(type_name: &str, action: TypeId, is_targeted: bool) -> Self {
+        let action_name: String = trim_type_name(type_name).into();
         let tag: String = gen_random_tag().to_ascii_lowercase();
         let name = action_name.clone() + "_" + &tag;
         Self {
             id: Uuid::new_v4(),
             name,
             action_name,
-            action: TypeId::of::<C>(),
-            type_registration: C::get_type_registration(),
+            action,
+            type_registrations: Vec::new(),
+            reflect_action: None,
+            delegate: None,
             is_targeted,
             base_score: 1.0,
             considerations: Vec::new(),
             subject_filters: Vec::new(),
             target_filters: Vec::new(),
             intertia: None,
+            compensation: None,
+            aggregation: DecisionAggregation::Product,
+            max_targets: None,
+            primary_consideration: None,
+            cooldown: None,
+            cooldown_per_target: false,
+            non_interruptible: false,
+            is_interrupt: false,
+            layer: None,
+            bucket: None,
         }
     }
 
     pub fn simple<C: Component + GetTypeRegistration>() -> Self {
-        Decision::construct::<C>(false)
+        Decision::construct::<C>(false, None)
     }
 
     pub fn targeted<C: Component + GetTypeRegistration>() -> Self {
-        Decision::construct::<C>(true)
+        Decision::construct::<C>(true, None)
+    }
+
+    /// A decision whose action is picked by another AIDefinition, the one registered for the
+    /// Marker Component `M`. When this decision wins, the decisions of that definition are
+    /// evaluated for the entity to pick the concrete action, in this decision's layer. The
+    /// decisions of that definition can't be placed in a layer of their own.
+    pub fn delegate<M: Component>() -> Self {
+        Self {
+            delegate: Some(TypeId::of::<M>()),
+            ..Decision::base(type_name::<M>(), TypeId::of::<M>(), false)
+        }
+    }
+
+    /// Like [`Decision::simple`] but the action is inserted as a clone of the given value
+    /// instead of its default, so decisions can share an action with different parameters.
+    pub fn simple_with<C: Component + GetTypeRegistration + Reflect>(action: C) -> Self {
+        Decision::construct::<C>(false, Some(Arc::new(action)))
+    }
+
+    /// Like [`Decision::targeted`] but the action is inserted as a clone of the given value
+    /// instead of its default, so decisions can share an action with different parameters.
+    pub fn targeted_with<C: Component + GetTypeRegistration + Reflect>(
+        action: C,
+    ) -> Self {
+        Decision::construct::<C>(true, Some(Arc::new(action)))
+    }
+
+    /// Inserts the given component together with the action, and removes it with the
+    /// action. The component must reflect Component.
+    pub fn with_component<C: Component + GetTypeRegistration + Reflect>(
+        self,
+        component: C,
+    ) -> Self {
+        self.add_extra_component::<C>(Some(Arc::new(component)))
+    }
+
+    /// Inserts the default of the given component together with the action, and removes it
+    /// with the action. The component must reflect Component and Default.
+    pub fn with_default_component<C: Component + GetTypeRegistration>(self) -> Self {
+        self.add_extra_component::<C>(None)
+    }
+
+    fn add_extra_component<C: Component + GetTypeRegistration>(
+        mut self,
+        prototype: Option<Arc<dyn Reflect>>,
+    ) -> Self {
+        let Some(reflect_action) = &mut self.reflect_action else {
+            panic!("Delegating decision '{}' has no action", self.name)
+        };
+        let component = ReflectActionComponent::of::<C>(prototype);
+        if reflect_action.type_id() == component.type_id
+            || reflect_action
+                .extra_components
+                .iter()
+                .any(|extra| extra.type_id == component.type_id)
+        {
+            panic!("Decision '{}' already inserts this component", self.name)
+        }
+        reflect_action.extra_components.push(component);
+        self.type_registrations.push(C::get_type_registration());
+        self
     }
 
     pub fn add_consideration(mut self, consideration: Consideration) -> Self {
@@ -90,6 +407,114 @@ impl Decision {
         self
     }
 
+    /// Adds a targeted consideration which is used to rank targets when the number of
+    /// targets is limited, see [`Decision::max_targets`].
+    pub fn add_primary_consideration(mut self, consideration: Consideration) -> Self {
+        if consideration.consideration_type != ConsiderationType::Targeted {
+            panic!(
+                "The primary consideration '{}' of decision '{}' must be targeted",
+                consideration.name, self.name
+            )
+        }
+        if self.primary_consideration.is_some() {
+            panic!(
+                "Decision '{}' already has a primary consideration",
+                self.name
+            )
+        }
+        self.primary_consideration = Some(self.considerations.len());
+        self.add_consideration(consideration)
+    }
+
+    /// Only considers the best K targets, ranked by the primary consideration, which is the
+    /// first targeted consideration unless set with [`Decision::add_primary_consideration`].
+    /// The remaining considerations are only calculated for these targets.
+    pub fn max_targets(mut self, k: usize) -> Self {
+        if !self.is_targeted {
+            panic!("Only targeted Decisions may limit their targets")
+        }
+        if k == 0 {
+            panic!("max_targets must be at least 1")
+        }
+        self.max_targets = Some(k);
+        self
+    }
+
+    /// Once an entity stops performing this decision's action it is not scored again for
+    /// that entity until the cooldown has expired.
+    pub fn with_cooldown(mut self, duration: Duration) -> Self {
+        if self.delegate.is_some() {
+            panic!("Delegating Decisions may not have cooldowns")
+        }
+        self.cooldown = Some(duration);
+        self.cooldown_per_target = false;
+        self
+    }
+
+    /// Like [`Decision::with_cooldown`] but the cooldown only applies to the target the
+    /// entity stopped acting on, other targets can still be scored.
+    pub fn with_target_cooldown(mut self, duration: Duration) -> Self {
+        if !self.is_targeted {
+            panic!("Only targeted Decisions may have target cooldowns")
+        }
+        self.cooldown = Some(duration);
+        self.cooldown_per_target = true;
+        self
+    }
+
+    /// Once picked, this decision's action can only be replaced by an interrupt until the
+    /// game signals it has completed, see [`ActionState`].
+    ///
+    /// [`ActionState`]: crate::ActionState
+    pub fn non_interruptible(mut self) -> Self {
+        if self.delegate.is_some() {
+            panic!("Delegating Decisions may not be non-interruptible")
+        }
+        self.non_interruptible = true;
+        self
+    }
+
+    /// Places this decision in a named action layer. Each layer picks its own action
+    /// independently of the others, decisions without a layer are in the default layer.
+    /// Decisions in different layers must not insert the same components.
+    ///
+    /// The target of a named layer's action is stored in the [`LayerTargets`] component,
+    /// and [`ActionState`] only applies to the default layer.
+    ///
+    /// [`LayerTargets`]: crate::LayerTargets
+    /// [`ActionState`]: crate::ActionState
+    pub fn in_layer(mut self, layer: impl Into<String>) -> Self {
+        self.layer = Some(layer.into());
+        self
+    }
+
+    /// Places this decision in a prioritised bucket, which must be added to the AI with
+    /// [`DefineUtilityAI::add_bucket`]. Decisions without a bucket are in a bucket with
+    /// priority 0 and threshold 0.0. Scores are compared with the thresholds before any
+    /// inertia is added.
+    ///
+    /// [`DefineUtilityAI::add_bucket`]: crate::define_ai::DefineUtilityAI::add_bucket
+    pub fn in_bucket(mut self, bucket: impl Into<String>) -> Self {
+        self.bucket = Some(bucket.into());
+        self
+    }
+
+    /// Allows this decision to replace a non-interruptible action that is still running,
+    /// see [`Decision::non_interruptible`].
+    pub fn as_interrupt(mut self) -> Self {
+        self.is_interrupt = true;
+        self
+    }
+
+    /// The index of the consideration used to rank targets.
+    pub(crate) fn primary_consideration_idx(&self) -> Option<usize> {
+        self.primary_consideration.or_else(|| {
+            self.considerations
+                .iter()
+                .position(|c| c.consideration_type == ConsiderationType::Targeted)
+        })
+    }
+
     pub fn subject_filter_include<C: Component>(mut self) -> Self {
         self.subject_filters
             .push(Filter::Inclusive(TypeId::of::<C>()));
@@ -146,6 +571,19 @@ impl Decision {
         self
     }
 
+    /// Sets how this decision compensates for its number of considerations, overriding the
+    /// AI's default compensation.
+    pub fn set_compensation(mut self, compensation: ConsiderationCompensation) -> Self {
+        self.compensation = Some(compensation);
+        self
+    }
+
+    /// Sets how the consideration scores are combined, by default they are multiplied.
+    pub fn set_aggregation(mut self, aggregation: DecisionAggregation) -> Self {
+        self.aggregation = aggregation;
+        self
+    }
+
     pub fn with_name(mut self, name: impl Into<String>) -> Self {
         self.name = name.into();
         self
@@ -1,8 +1,12 @@
 use crate::{
     considerations::ConsiderationType,
-    decisions::Decision,
+    decisions::{ConsiderationCompensation, Decision, DecisionBucket, DecisionSelection},
     plugin::{UtilityAISet, UtilityAISettings},
-    systems::{ensure_entity_has_ai_meta, handle_ai_marker_removed},
+    systems::{
+        budget::{schedule_evaluation_sys, UpdateBudget},
+        ensure_entity_has_ai_meta, handle_ai_marker_removed,
+        make_decisions::MAX_DELEGATION_DEPTH,
+    },
     AIDefinition, AIDefinitions, FilterDefinition, TargetedInputRequirements,
 };
 use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
@@ -10,7 +14,9 @@ use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
 use crate::utils::trim_type_name;
 use bevy::{
     app::App,
-    prelude::{AppTypeRegistry, Component, IntoSystemConfigs, Resource},
+    ecs::schedule::Schedules,
+    log::info,
+    prelude::{AppTypeRegistry, Component, IntoSystemConfigs, Resource, World},
     reflect::TypeRegistration,
     utils::{HashMap, HashSet},
 };
@@ -33,6 +39,10 @@ pub struct DefineUtilityAI<T: Component> {
     /// A vec of all actions defined as part of this AI, will be registered to the App.
     action_type_registrations: Vec<TypeRegistration>,
     default_intertia: f32,
+    default_compensation: ConsiderationCompensation,
+    selection: DecisionSelection,
+    buckets: Vec<DecisionBucket>,
+    update_budget: UpdateBudget,
     marker_phantom: PhantomData<T>,
     schedule_label: Option<InternedScheduleLabel>,
 }
@@ -48,39 +58,22 @@ impl<T: Component> DefineUtilityAI<T> {
             action_type_registrations: Vec::new(),
             schedule_label: None,
             default_intertia: 0.0,
+            default_compensation: ConsiderationCompensation::None,
+            selection: DecisionSelection::Highest,
+            buckets: Vec::new(),
+            update_budget: UpdateBudget::EveryFrame,
         }
     }
 
     pub fn add_decision(mut self, decision: Decision) -> DefineUtilityAI<T> {
-        for consideration in &decision.considerations {
-            match consideration.consideration_type {
-                ConsiderationType::Simple => {
-                    self.simple_inputs.insert(consideration.input);
-                }
-                ConsiderationType::Targeted => {
-                    let filter_definition = match &decision.target_filters.is_empty() {
-                        true => FilterDefinition::Any,
-                        false => FilterDefinition::Filtered(vec![decision
-                            .target_filters
-                            .clone()]),
-                    };
-                    if let Some(req) = self.targeted_inputs.get_mut(&consideration.input)
-                    {
-                        req.target_filter = req.target_filter.merge(&filter_definition)
-                    } else {
-                        self.targeted_inputs.insert(
-                            consideration.input,
-                            TargetedInputRequirements {
-                                target_filter: filter_definition,
-                            },
-                        );
-                    }
-                }
-            };
-        }
+        add_decision_inputs(
+            &mut self.simple_inputs,
+            &mut self.targeted_inputs,
+            &decision,
+        );
 
         self.action_type_registrations
-            .push(decision.type_registration.clone());
+            .extend(decision.type_registrations.iter().cloned());
         self.decisions.push(decision);
 
         self
@@ -99,14 +92,109 @@ impl<T: Component> DefineUtilityAI<T> {
         self
     }
 
+    /// Sets how decisions compensate for their number of considerations, unless overridden
+    /// by the decision itself. By default no compensation is applied.
+    pub fn set_default_compensation(
+        mut self,
+        compensation: ConsiderationCompensation,
+    ) -> DefineUtilityAI<T> {
+        self.default_compensation = compensation;
+        self
+    }
+
+    /// Sets how the decision to act on is picked, by default the highest scoring decision is
+    /// always picked.
+    pub fn set_selection(mut self, selection: DecisionSelection) -> DefineUtilityAI<T> {
+        match selection {
+            DecisionSelection::TopN(0) => {
+                panic!("TopN must pick from at least 1 decision")
+            }
+            DecisionSelection::WithinPercent(percent)
+                if !(0.0..=1.0).contains(&percent) =>
+            {
+                panic!("percent must be between 0.0 and 1.0")
+            }
+            DecisionSelection::Softmax { temperature } if temperature <= 0.0 => {
+                panic!("temperature must be greater than 0.0")
+            }
+            _ => {}
+        }
+        self.selection = selection;
+        self
+    }
+
+    /// Adds a prioritised bucket of decisions, see [`Decision::in_bucket`]. A decision in a
+    /// higher priority bucket that scores above the bucket's threshold always wins over
+    /// the decisions in lower priority buckets.
+    pub fn add_bucket(
+        mut self,
+        name: impl Into<String>,
+        priority: i32,
+        threshold: f32,
+    ) -> DefineUtilityAI<T> {
+        let name = name.into();
+        if self.buckets.iter().any(|bucket| bucket.name == name) {
+            panic!("Bucket '{name}' has already been added")
+        }
+        if threshold.is_nan() || threshold < 0.0 {
+            panic!("threshold must be at least 0.0")
+        }
+        self.buckets.push(DecisionBucket {
+            name,
+            priority,
+            threshold,
+        });
+        self
+    }
+
+    /// Sets how often the entities of this AI are evaluated, by default every entity is
+    /// evaluated every frame.
+    pub fn set_update_budget(
+        mut self,
+        update_budget: UpdateBudget,
+    ) -> DefineUtilityAI<T> {
+        match update_budget {
+            UpdateBudget::MaxEntitiesPerFrame(0) => {
+                panic!("MaxEntitiesPerFrame must evaluate at least 1 entity")
+            }
+            UpdateBudget::Interval(interval) if interval < 0.0 => {
+                panic!("interval must be >= 0.0")
+            }
+            _ => {}
+        }
+        self.update_budget = update_budget;
+        self
+    }
+
     /// Registers the defined AI against the bevy App, this should be called as the last step of
-    /// the defineAI process.
-    pub fn register(mut self, app: &mut App) {
-        // note all these actions are idempotent except app.add_system, so we maintain a resource on
-        // the app to track systems that are already added.
+    /// the defineAI process. If an AI is already defined for the marker component it is
+    /// replaced. Decisions at the same position with the same action keep their identity, so
+    /// their cooldowns carry over, and entities keep performing an action that is unchanged.
+    pub fn register(self, app: &mut App) {
+        self.register_in_world(app.world_mut())
+    }
+
+    /// Registers the defined AI directly against a World, this behaves the same as `register`
+    /// but can be used from exclusive systems once the App is running. Note that systems
+    /// cannot be added to a schedule while that schedule is being run.
+    pub fn register_in_world(mut self, world: &mut World) {
+        if let Some(ai_definitions) = world.get_resource::<AIDefinitions>() {
+            let marker = TypeId::of::<T>();
+            if let Err(reason) =
+                check_action_layers(ai_definitions, marker, &self.decisions)
+            {
+                panic!("{reason}")
+            }
+        }
+
+        for decision in &self.decisions {
+            check_bucket(&self.buckets, decision);
+        }
+
+        // note all these actions are idempotent except adding systems, so we maintain a resource on
+        // the world to track systems that are already added.
         {
-            let mut added_systems = app
-                .world_mut()
+            let mut added_systems = world
                 .remove_resource::<AddedSystemTracker>()
                 .unwrap_or_else(|| {
                     panic!("Make sure the plugin is added to the app before calls to DefineAI")
@@ -114,60 +202,66 @@ impl<T: Component> DefineUtilityAI<T> {
 
             let schedule_label = self
                 .schedule_label
-                .unwrap_or(app.world().resource::<UtilityAISettings>().default_schedule);
+                .unwrap_or(world.resource::<UtilityAISettings>().default_schedule);
 
-            app.add_systems(
-                schedule_label,
-                (
-                    ensure_entity_has_ai_meta::<T>.in_set(UtilityAISet::Prepare),
-                    handle_ai_marker_removed::<T>.in_set(UtilityAISet::Tidyup),
-                ),
-            );
+            let mut schedules = world.resource_mut::<Schedules>();
+
+            if !added_systems.markers.contains_key(&TypeId::of::<T>()) {
+                schedules.add_systems(
+                    schedule_label,
+                    (
+                        ensure_entity_has_ai_meta::<T>.in_set(UtilityAISet::Prepare),
+                        schedule_evaluation_sys::<T>.in_set(UtilityAISet::Prepare),
+                        handle_ai_marker_removed::<T>.in_set(UtilityAISet::Tidyup),
+                    ),
+                );
+                added_systems
+                    .markers
+                    .insert(TypeId::of::<T>(), schedule_label);
+            }
 
             // Add utility systems
             for decision in &mut self.decisions {
-                decision.considerations.iter_mut().for_each(|c| {
-                    let system_app_config = c.system_app_config.take().unwrap();
-                    if !added_systems.systems.contains(&c.input) {
-                        app.add_systems(
-                            schedule_label,
-                            system_app_config.in_set(UtilityAISet::CalculateInputs),
-                        );
-                        added_systems.systems.insert(c.input);
-                    }
-                });
+                add_input_systems(
+                    &mut schedules,
+                    &mut added_systems,
+                    schedule_label,
+                    decision,
+                );
             }
 
-            app.world_mut().insert_resource(added_systems);
+            world.insert_resource(added_systems);
         }
 
         // Register actions with the AppTypeRegistry
+        register_action_types(world, self.action_type_registrations);
+
+        // Add the AIDefinition to the AIDefinitions resource, replacing any existing
+        // definition in place so that entities keep their AIMeta and current action.
+        let mut ai_definition = AIDefinition {
+            name: self.name,
+            marker_type: TypeId::of::<T>(),
+            decisions: self.decisions,
+            simple_inputs: self.simple_inputs,
+            targeted_inputs: self.targeted_inputs,
+            default_intertia: self.default_intertia,
+            default_compensation: self.default_compensation,
+            selection: self.selection,
+            buckets: self.buckets,
+            update_budget: self.update_budget,
+        };
+
+        let mut ai_definitions = world.resource_mut::<AIDefinitions>();
+        if let Some(previous) = ai_definitions.map.get(&TypeId::of::<T>()) {
+            keep_decision_ids(&previous.decisions, &mut ai_definition.decisions);
+        }
+        if let Some(previous) = ai_definitions
+            .map
+            .insert(ai_definition.marker_type, ai_definition)
         {
-            let registry = app.world_mut().resource_mut::<AppTypeRegistry>();
-            let mut registry_write = registry.write();
-            self.action_type_registrations.into_iter().for_each(|f| {
-                registry_write.add_registration(f);
-            });
-        }
-
-        // Add the AIDefinition to the AIDefinitions resource
-        let mut ai_definitions = app.world_mut().resource_mut::<AIDefinitions>();
-
-        if !ai_definitions.map.contains_key(&TypeId::of::<T>()) {
-            let ai_definition = AIDefinition {
-                name: self.name,
-                marker_type: TypeId::of::<T>(),
-                decisions: self.decisions,
-                simple_inputs: self.simple_inputs,
-                targeted_inputs: self.targeted_inputs,
-                default_intertia: self.default_intertia,
-            };
-            ai_definitions
-                .map
-                .insert(ai_definition.marker_type, ai_definition);
-        } else {
-            panic!("AI is already defined for this marker component!")
+            info!("Replaced AI definition '{}'", previous.name);
         }
+        ai_definitions.merge_delegate_inputs();
     }
 }
 
@@ -177,7 +271,179 @@ impl<T: Component> Default for DefineUtilityAI<T> {
     }
 }
 
+/// Gives the replacement decisions the ids of the previous decisions at the same position
+/// with the same action, as cooldowns are tracked by decision id.
+fn keep_decision_ids(previous: &[Decision], decisions: &mut [Decision]) {
+    for (previous, decision) in previous.iter().zip(decisions) {
+        if previous.action == decision.action
+            && previous.is_targeted == decision.is_targeted
+            && previous.delegate == decision.delegate
+        {
+            decision.id = previous.id;
+        }
+    }
+}
+
+/// Checks that no component is inserted by decisions in different action layers, as a
+/// layer changing its action would remove the component from under the other layer. The
+/// decisions of a definition that is delegated to are in the delegating decision's layer,
+/// so they can't be placed in a layer of their own.
+///
+/// The decisions are checked as if they were registered for the marker, together with all
+/// the AIDefinitions that are already registered.
+pub(crate) fn check_action_layers(
+    ai_definitions: &AIDefinitions,
+    marker: TypeId,
+    decisions: &[Decision],
+) -> Result<(), String> {
+    let decisions_of = |marker_type: TypeId| match marker_type == marker {
+        true => Some(decisions),
+        false => ai_definitions
+            .map
+            .get(&marker_type)
+            .map(|ai_definition| ai_definition.decisions.as_slice()),
+    };
+    let mut markers = Vec::from_iter(ai_definitions.map.keys().copied());
+    if !markers.contains(&marker) {
+        markers.push(marker);
+    }
+
+    for &marker_type in &markers {
+        let decisions = decisions_of(marker_type).unwrap();
+        for delegate in decisions.iter().filter_map(|decision| decision.delegate) {
+            let delegate_decisions = decisions_of(delegate).unwrap_or_default();
+            if let Some(decision) = delegate_decisions.iter().find(|d| d.layer.is_some())
+            {
+                return Err(format!(
+                    "decision '{}' is delegated to, so it can't be in an action layer",
+                    decision.name
+                ));
+            }
+        }
+        check_layer_components(&decisions_of, decisions, None, 0, &mut HashMap::new())?;
+    }
+    Ok(())
+}
+
+fn check_layer_components<'a>(
+    decisions_of: &impl Fn(TypeId) -> Option<&'a [Decision]>,
+    decisions: &'a [Decision],
+    delegating_layer: Option<Option<&'a str>>,
+    depth: usize,
+    inserted_by: &mut HashMap<TypeId, (&'a Decision, Option<&'a str>)>,
+) -> Result<(), String> {
+    for decision in decisions {
+        let layer = delegating_layer.unwrap_or(decision.layer.as_deref());
+        if let Some(delegate) = decision.delegate {
+            // cyclic delegation is only detected when the decisions are made
+            if let (true, Some(delegate_decisions)) =
+                (depth < MAX_DELEGATION_DEPTH, decisions_of(delegate))
+            {
+                check_layer_components(
+                    decisions_of,
+                    delegate_decisions,
+                    Some(layer),
+                    depth + 1,
+                    inserted_by,
+                )?;
+            }
+            continue;
+        }
+        for component in decision.reflect_action.iter().flat_map(|a| a.components()) {
+            let (other, other_layer) = *inserted_by
+                .entry(component.type_id)
+                .or_insert((decision, layer));
+            if other_layer != layer {
+                return Err(format!(
+                    "decisions '{}' and '{}' share a component across action layers",
+                    other.name, decision.name
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
 #[derive(Resource, Default)]
 pub(crate) struct AddedSystemTracker {
+    /// The input systems that have been added.
     pub(crate) systems: HashSet<TypeId>,
+    /// The Marker Components whose prepare & tidyup systems have been added, and the
+    /// schedule they were added to.
+    pub(crate) markers: HashMap<TypeId, InternedScheduleLabel>,
+}
+
+/// Adds the inputs of the decision's considerations to the inputs of an AI.
+pub(crate) fn add_decision_inputs(
+    simple_inputs: &mut HashSet<TypeId>,
+    targeted_inputs: &mut HashMap<TypeId, TargetedInputRequirements>,
+    decision: &Decision,
+) {
+    for consideration in &decision.considerations {
+        match consideration.consideration_type {
+            ConsiderationType::Simple => {
+                simple_inputs.insert(consideration.input);
+            }
+            ConsiderationType::Targeted => {
+                let filter_definition = match &decision.target_filters.is_empty() {
+                    true => FilterDefinition::Any,
+                    false => {
+                        FilterDefinition::Filtered(vec![decision.target_filters.clone()])
+                    }
+                };
+                if let Some(req) = targeted_inputs.get_mut(&consideration.input) {
+                    req.target_filter = req.target_filter.merge(&filter_definition)
+                } else {
+                    targeted_inputs.insert(
+                        consideration.input,
+                        TargetedInputRequirements {
+                            target_filter: filter_definition,
+                        },
+                    );
+                }
+            }
+        };
+    }
+}
+
+/// Adds the input systems of the decision's considerations to the schedule, unless they
+/// have already been added.
+pub(crate) fn add_input_systems(
+    schedules: &mut Schedules,
+    added_systems: &mut AddedSystemTracker,
+    schedule_label: InternedScheduleLabel,
+    decision: &mut Decision,
+) {
+    for consideration in &mut decision.considerations {
+        let system_app_config = consideration.system_app_config.take().unwrap();
+        if !added_systems.systems.contains(&consideration.input) {
+            schedules.add_systems(
+                schedule_label,
+                system_app_config.in_set(UtilityAISet::CalculateInputs),
+            );
+            added_systems.systems.insert(consideration.input);
+        }
+    }
+}
+
+pub(crate) fn register_action_types(
+    world: &mut World,
+    registrations: impl IntoIterator<Item = TypeRegistration>,
+) {
+    let registry = world.resource_mut::<AppTypeRegistry>();
+    let mut registry_write = registry.write();
+    registrations.into_iter().for_each(|f| {
+        registry_write.add_registration(f);
+    });
+}
+
+pub(crate) fn check_bucket(buckets: &[DecisionBucket], decision: &Decision) {
+    if let Some(bucket) = &decision.bucket {
+        if !buckets.iter().any(|b| &b.name == bucket) {
+            panic!(
+                "Decision '{}' is in bucket '{}' which hasn't been added",
+                decision.name, bucket
+            )
+        }
+    }
 }
@@ -0,0 +1,212 @@
+use crate::{
+    decisions::Decision,
+    define_ai::{
+        add_input_systems, check_action_layers, check_bucket, register_action_types,
+        AddedSystemTracker,
+    },
+    response_curves::ResponseCurve,
+    systems::update_action::end_entity_actions,
+    AIDefinition, AIDefinitions, AIMeta,
+};
+use bevy::{
+    ecs::schedule::Schedules,
+    log::info,
+    prelude::{Component, Entity, World},
+};
+use std::any::TypeId;
+
+/// Extends the World with methods to change registered AI definitions once the App is
+/// running, e.g. from exclusive systems. Note that systems cannot be added to a schedule
+/// while that schedule is being run.
+pub trait UtilityAIWorldExt {
+    /// Edits the AI definition registered for the marker component, returns None if no
+    /// definition is registered.
+    fn edit_ai<T: Component>(&mut self) -> Option<EditUtilityAI<'_>>;
+
+    /// Removes the AI definition registered for the marker component, returns whether a
+    /// definition was registered. The actions of entities with the marker component are
+    /// ended, and they stop making decisions until a definition is registered again.
+    fn unregister_ai<T: Component>(&mut self) -> bool;
+}
+
+impl UtilityAIWorldExt for World {
+    fn edit_ai<T: Component>(&mut self) -> Option<EditUtilityAI<'_>> {
+        let marker = TypeId::of::<T>();
+        if !self.resource::<AIDefinitions>().map.contains_key(&marker) {
+            return None;
+        }
+        Some(EditUtilityAI {
+            world: self,
+            marker,
+        })
+    }
+
+    fn unregister_ai<T: Component>(&mut self) -> bool {
+        let mut ai_definitions = self.resource_mut::<AIDefinitions>();
+        let Some(ai_definition) = ai_definitions.map.remove(&TypeId::of::<T>()) else {
+            return false;
+        };
+        info!("Unregistered AI definition '{}'", ai_definition.name);
+        ai_definitions.recalculate_inputs();
+
+        let marker = TypeId::of::<T>();
+        let entities = Vec::from_iter(
+            self.query::<(Entity, &AIMeta)>()
+                .iter(self)
+                .filter(|(_, ai_meta)| ai_meta.ai_definition == marker)
+                .map(|(entity, _)| entity),
+        );
+        for entity in entities {
+            let mut entity_mut = self.entity_mut(entity);
+            let ai_meta = entity_mut.take::<AIMeta>().unwrap();
+            entity_mut.insert(AIMeta::new::<T>());
+            end_entity_actions(self, entity, ai_meta);
+        }
+        true
+    }
+}
+
+/// Changes a registered AI definition in place, so that entities keep their AIMeta and
+/// current action. Decisions and considerations are referred to by name.
+pub struct EditUtilityAI<'w> {
+    world: &'w mut World,
+    marker: TypeId,
+}
+
+impl EditUtilityAI<'_> {
+    /// Adds the decision to the AI definition, adding the input systems of its
+    /// considerations to the schedule the AI was registered in.
+    pub fn add_decision(&mut self, mut decision: Decision) -> &mut Self {
+        check_bucket(&self.ai_definition().buckets, &decision);
+
+        let mut added_systems = self
+            .world
+            .remove_resource::<AddedSystemTracker>()
+            .unwrap_or_else(|| {
+                panic!(
+                    "Make sure the plugin is added to the app before calls to DefineAI"
+                )
+            });
+        let schedule_label = added_systems.markers[&self.marker];
+        add_input_systems(
+            &mut self.world.resource_mut::<Schedules>(),
+            &mut added_systems,
+            schedule_label,
+            &mut decision,
+        );
+        self.world.insert_resource(added_systems);
+
+        register_action_types(self.world, decision.type_registrations.iter().cloned());
+
+        self.edit_ai_definition(|ai_definition| ai_definition.decisions.push(decision));
+        let ai_definitions = self.world.resource::<AIDefinitions>();
+        let decisions = &ai_definitions.map[&self.marker].decisions;
+        if let Err(reason) = check_action_layers(ai_definitions, self.marker, decisions) {
+            self.edit_ai_definition(|ai_definition| {
+                ai_definition.decisions.pop();
+            });
+            panic!("{reason}")
+        }
+        self.world
+            .resource_mut::<AIDefinitions>()
+            .recalculate_inputs();
+        self
+    }
+
+    /// Removes the decision from the AI definition. Its input systems stay in the
+    /// schedule but no longer calculate inputs for this AI.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the AI definition has no decision with the given name.
+    pub fn remove_decision(&mut self, decision: &str) -> &mut Self {
+        let idx = self.decision_idx(decision);
+        self.edit_ai_definition(|ai_definition| {
+            ai_definition.decisions.remove(idx);
+        });
+        self.world
+            .resource_mut::<AIDefinitions>()
+            .recalculate_inputs();
+        self
+    }
+
+    /// Replaces the response curve of one of the decision's considerations, returns an error
+    /// if the curve's parameters are invalid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the decision or consideration doesn't exist.
+    pub fn set_response_curve(
+        &mut self,
+        decision: &str,
+        consideration: &str,
+        response_curve: impl Into<ResponseCurve>,
+    ) -> Result<&mut Self, String> {
+        let idx = self.decision_idx(decision);
+        let response_curve = response_curve.into();
+        response_curve.validate()?;
+        self.edit_ai_definition(|ai_definition| {
+            let decision = &mut ai_definition.decisions[idx];
+            let Some(consideration) = decision
+                .considerations
+                .iter_mut()
+                .find(|c| c.name == consideration)
+            else {
+                panic!(
+                    "Decision '{}' has no consideration named '{}'",
+                    decision.name, consideration
+                )
+            };
+            consideration.response_curve = response_curve;
+        });
+        Ok(self)
+    }
+
+    /// Sets the intertia of the decision, see [`Decision::set_intertia`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the decision doesn't exist or the intertia is out of range.
+    pub fn set_intertia(&mut self, decision: &str, intertia: f32) -> &mut Self {
+        if !(0.0..1.0).contains(&intertia) {
+            panic!("intertia must be between 0.0 and 1.0");
+        }
+        let idx = self.decision_idx(decision);
+        self.edit_ai_definition(|ai_definition| {
+            ai_definition.decisions[idx].intertia = Some(intertia);
+        });
+        self
+    }
+
+    /// Sets the intertia used by decisions which don't set their own.
+    pub fn set_default_intertia(&mut self, value: f32) -> &mut Self {
+        if !(0.0..1.0).contains(&value) {
+            panic!("value must be between =0.0 and 1.0");
+        }
+        self.edit_ai_definition(|ai_definition| ai_definition.default_intertia = value);
+        self
+    }
+
+    fn ai_definition(&self) -> &AIDefinition {
+        &self.world.resource::<AIDefinitions>().map[&self.marker]
+    }
+
+    fn edit_ai_definition(&mut self, edit: impl FnOnce(&mut AIDefinition)) {
+        let mut ai_definitions = self.world.resource_mut::<AIDefinitions>();
+        edit(ai_definitions.map.get_mut(&self.marker).unwrap());
+    }
+
+    fn decision_idx(&self, decision: &str) -> usize {
+        let ai_definition = self.ai_definition();
+        ai_definition
+            .decisions
+            .iter()
+            .position(|d| d.name == decision)
+            .unwrap_or_else(|| {
+                panic!(
+                    "AI definition '{}' has no decision named '{}'",
+                    ai_definition.name, decision
+                )
+            })
+    }
+}
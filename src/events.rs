@@ -1,4 +1,5 @@
 use bevy::prelude::{Entity, Event};
+use std::{marker::PhantomData, time::Duration};
 use uuid::Uuid;
 
 #[derive(Event)]
@@ -38,3 +39,42 @@ pub struct DecisionCalculatedEvent {
     /// The calculated score
     pub score: f32,
 }
+
+/// Triggered on an entity when the AI inserts the Action Component `A`, it can be observed
+/// with an [`Observer`].
+///
+/// [`Observer`]: bevy::ecs::observer::Observer
+#[derive(Event)]
+pub struct ActionStarted<A> {
+    /// The target of the action if it is a targeted action
+    pub target: Option<Entity>,
+    /// The score of the decision that started the action
+    pub score: f32,
+    pub(crate) action: PhantomData<fn() -> A>,
+}
+
+/// Triggered on an entity when the AI removes the Action Component `A`, it can be observed
+/// with an [`Observer`].
+///
+/// [`Observer`]: bevy::ecs::observer::Observer
+#[derive(Event)]
+pub struct ActionEnded<A> {
+    /// The target of the action if it was a targeted action
+    pub target: Option<Entity>,
+    /// The last score of the decision for the action
+    pub score: f32,
+    /// How long the action ran for
+    pub duration: Duration,
+    pub(crate) action: PhantomData<fn() -> A>,
+}
+
+/// Signals that an entity's current action has completed, this sets its [`ActionState`].
+///
+/// [`ActionState`]: crate::ActionState
+#[derive(Event)]
+pub struct ActionCompletedEvent {
+    /// The Entity whose action has completed
+    pub entity: Entity,
+    /// Whether the action succeeded
+    pub success: bool,
+}
@@ -8,22 +8,30 @@ use bevy::{
 pub use bevy_utility_ai_macros::{input_system, targeted_input_system};
 
 pub use crate::ai_meta::AIMeta;
-use crate::decisions::{Decision, Filter};
+use crate::decisions::{
+    ConsiderationCompensation, Decision, DecisionBucket, DecisionSelection, Filter,
+};
+use crate::systems::budget::UpdateBudget;
 
 pub mod ai_meta;
 pub mod considerations;
 pub mod decisions;
 pub mod define_ai;
+pub mod edit_ai;
+pub mod overrides;
 pub mod plugin;
 pub mod response_curves;
+pub mod spatial;
 pub mod systems;
 pub mod utils;
 
+#[cfg(feature = "assets")]
+pub mod asset;
 #[cfg(feature = "dashboard")]
 pub mod dashboard;
 pub mod events;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum FilterDefinition {
     Any,
     Filtered(Vec<Vec<Filter>>),
@@ -48,6 +56,30 @@ impl FilterDefinition {
     }
 }
 
+impl FilterDefinition {
+    /// Widens this definition to also allow the targets of the other, returns whether it
+    /// changed.
+    fn include(&mut self, other: &FilterDefinition) -> bool {
+        match (&mut *self, other) {
+            (FilterDefinition::Any, _) => false,
+            (_, FilterDefinition::Any) => {
+                *self = FilterDefinition::Any;
+                true
+            }
+            (FilterDefinition::Filtered(x), FilterDefinition::Filtered(y)) => {
+                let mut changed = false;
+                for filters in y {
+                    if !x.contains(filters) {
+                        x.push(filters.clone());
+                        changed = true;
+                    }
+                }
+                changed
+            }
+        }
+    }
+}
+
 pub struct TargetedInputRequirements {
     pub target_filter: FilterDefinition,
 }
@@ -59,6 +91,14 @@ pub struct AIDefinition {
     pub marker_type: TypeId,
     /// The default value to use for the intertia of a decision if unspecified
     pub default_intertia: f32,
+    /// The default consideration compensation to use for a decision if unspecified
+    pub default_compensation: ConsiderationCompensation,
+    /// How the decision to act on is picked from the evaluated decisions
+    pub selection: DecisionSelection,
+    /// The prioritised buckets the decisions are grouped into
+    pub buckets: Vec<DecisionBucket>,
+    /// Limits how often the entities of this AI are evaluated
+    pub update_budget: UpdateBudget,
     /// The decisions that make up this AIDefinition
     pub decisions: Vec<Decision>,
     /// The simple inputs used for this AI, passed to AIDefinition on register.
@@ -68,6 +108,15 @@ pub struct AIDefinition {
 }
 
 impl AIDefinition {
+    /// The priority and threshold of the decision's bucket, see [`Decision::in_bucket`].
+    pub(crate) fn bucket_of(&self, decision: &Decision) -> (i32, f32) {
+        decision
+            .bucket
+            .as_ref()
+            .and_then(|name| self.buckets.iter().find(|bucket| &bucket.name == name))
+            .map_or((0, 0.0), |bucket| (bucket.priority, bucket.threshold))
+    }
+
     pub fn requires_targeted_input(&self, input: &TypeId) -> bool {
         self.targeted_inputs.contains_key(input)
     }
@@ -90,8 +139,107 @@ pub struct AIDefinitions {
     pub map: HashMap<TypeId, AIDefinition>,
 }
 
+impl AIDefinitions {
+    /// Recalculates the inputs of every definition from its decisions, so that inputs which
+    /// are no longer used stop being calculated.
+    pub(crate) fn recalculate_inputs(&mut self) {
+        for ai_definition in self.map.values_mut() {
+            ai_definition.simple_inputs.clear();
+            ai_definition.targeted_inputs.clear();
+            for decision in &ai_definition.decisions {
+                define_ai::add_decision_inputs(
+                    &mut ai_definition.simple_inputs,
+                    &mut ai_definition.targeted_inputs,
+                    decision,
+                );
+            }
+        }
+        self.merge_delegate_inputs();
+    }
+
+    /// Adds the inputs of the definitions that are delegated to, see [`Decision::delegate`],
+    /// to the definitions delegating to them so that their input systems calculate them.
+    pub(crate) fn merge_delegate_inputs(&mut self) {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            let markers = Vec::from_iter(self.map.keys().copied());
+            for marker in markers {
+                let delegates = Vec::from_iter(
+                    self.map[&marker]
+                        .decisions
+                        .iter()
+                        .filter_map(|decision| decision.delegate)
+                        .filter(|delegate| *delegate != marker),
+                );
+                for delegate in delegates {
+                    let Some(delegate) = self.map.get(&delegate) else {
+                        continue;
+                    };
+                    let simple_inputs = delegate.simple_inputs.clone();
+                    let targeted_inputs = Vec::from_iter(
+                        delegate
+                            .targeted_inputs
+                            .iter()
+                            .map(|(input, req)| (*input, req.target_filter.clone())),
+                    );
+
+                    let ai_definition = self.map.get_mut(&marker).unwrap();
+                    for input in simple_inputs {
+                        changed |= ai_definition.simple_inputs.insert(input);
+                    }
+                    for (input, target_filter) in targeted_inputs {
+                        match ai_definition.targeted_inputs.get_mut(&input) {
+                            Some(req) => {
+                                changed |= req.target_filter.include(&target_filter)
+                            }
+                            None => {
+                                ai_definition.targeted_inputs.insert(
+                                    input,
+                                    TargetedInputRequirements { target_filter },
+                                );
+                                changed = true;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// A component to hold the Target entity ID
 #[derive(Component)]
 pub struct ActionTarget {
     pub target: Entity,
 }
+
+/// A component to hold the Target entity IDs of an entity's named action layers, see
+/// [`Decision::in_layer`].
+#[derive(Component, Default)]
+pub struct LayerTargets {
+    pub targets: HashMap<String, Entity>,
+}
+
+/// The state of an entity's current action. It is set to [`ActionState::Running`] whenever
+/// the action changes, the game signals the action's completion by setting it to
+/// [`ActionState::Success`] or [`ActionState::Failure`], either directly or by sending an
+/// [`ActionCompletedEvent`].
+///
+/// A completed action is re-evaluated as if the entity had no current action, so it is
+/// restarted if it is picked again.
+///
+/// [`ActionCompletedEvent`]: crate::events::ActionCompletedEvent
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ActionState {
+    #[default]
+    Running,
+    Success,
+    Failure,
+}
+
+impl ActionState {
+    pub fn is_finished(&self) -> bool {
+        *self != ActionState::Running
+    }
+}
@@ -0,0 +1,104 @@
+use crate::considerations::Consideration;
+use crate::response_curves::ResponseCurve;
+use crate::Decision;
+use bevy::prelude::Component;
+use bevy::utils::{HashMap, HashSet};
+
+/// Per entity adjustments to the entity's AIDefinition, so that entities sharing a Marker
+/// Component don't have to behave identically. Decisions and considerations are referred
+/// to by name, see [`Decision::with_name`] and [`Consideration::with_name`].
+#[derive(Component, Clone, Default)]
+pub struct AIOverrides {
+    /// Multipliers for the base score of decisions.
+    pub base_score_multipliers: HashMap<String, f32>,
+    /// Replacement weights for considerations.
+    pub consideration_weights: HashMap<String, f32>,
+    /// Replacement response curves for considerations.
+    pub response_curves: HashMap<String, ResponseCurve>,
+    /// Decisions which are never scored for the entity.
+    pub disabled_decisions: HashSet<String>,
+}
+
+impl AIOverrides {
+    /// Multiplies the base score of the decision by the given multiplier.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the multiplier is negative.
+    pub fn scale_base_score(
+        mut self,
+        decision: impl Into<String>,
+        multiplier: f32,
+    ) -> Self {
+        if multiplier < 0.0 {
+            panic!("multiplier must be at least 0.0")
+        }
+        self.base_score_multipliers
+            .insert(decision.into(), multiplier);
+        self
+    }
+
+    /// Replaces the weight of the consideration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the weight is negative.
+    pub fn with_weight(mut self, consideration: impl Into<String>, weight: f32) -> Self {
+        if weight < 0.0 {
+            panic!("weight must be at least 0.0")
+        }
+        self.consideration_weights
+            .insert(consideration.into(), weight);
+        self
+    }
+
+    /// Replaces the response curve of the consideration.
+    pub fn with_response_curve(
+        mut self,
+        consideration: impl Into<String>,
+        response_curve: impl Into<ResponseCurve>,
+    ) -> Self {
+        self.response_curves
+            .insert(consideration.into(), response_curve.into());
+        self
+    }
+
+    /// Stops the decision from being scored for the entity.
+    pub fn disable_decision(mut self, decision: impl Into<String>) -> Self {
+        self.disabled_decisions.insert(decision.into());
+        self
+    }
+
+    pub(crate) fn is_disabled(&self, decision: &Decision) -> bool {
+        self.disabled_decisions.contains(&decision.name)
+    }
+
+    pub(crate) fn base_score(&self, decision: &Decision) -> f32 {
+        decision.base_score
+            * self
+                .base_score_multipliers
+                .get(&decision.name)
+                .copied()
+                .unwrap_or(1.0)
+    }
+
+    pub(crate) fn weight(&self, consideration: &Consideration) -> f32 {
+        self.consideration_weights
+            .get(&consideration.name)
+            .copied()
+            .unwrap_or(consideration.weight)
+    }
+
+    pub(crate) fn calculate_score(
+        &self,
+        consideration: &Consideration,
+        input_score: f32,
+    ) -> f32 {
+        match self.response_curves.get(&consideration.name) {
+            Some(response_curve) => {
+                consideration.calculate_score_with(response_curve, input_score)
+            }
+            None => consideration.calculate_score(input_score),
+        }
+    }
+}
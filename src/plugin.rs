@@ -1,17 +1,19 @@
 use crate::define_ai::AddedSystemTracker;
 use crate::events::{
-    ConsiderationCalculatedEvent, DecisionCalculatedEvent, InputCalculatedEvent,
+    ActionCompletedEvent, ConsiderationCalculatedEvent, DecisionCalculatedEvent,
+    InputCalculatedEvent,
 };
 use crate::systems::make_decisions::{make_decisions_sys, EntityActionChangedEvent};
 use crate::systems::update_action::{
-    update_actions_sys, UpdateEntityActionInternalEvent,
+    complete_actions_sys, update_actions_sys, UpdateEntityActionInternalEvent,
 };
 use crate::AIDefinitions;
 use bevy::app::Update;
 use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
 use bevy::prelude::{
-    IntoSystemConfigs, IntoSystemSetConfigs, Plugin, Resource, SystemSet,
+    IntoSystemConfigs, IntoSystemSetConfigs, Plugin, Resource, SystemSet, Time,
 };
+use rand::{rngs::StdRng, SeedableRng};
 
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
 pub enum UtilityAISet {
@@ -22,6 +24,23 @@ pub enum UtilityAISet {
     Tidyup,
 }
 
+/// The source of randomness for the framework, e.g. for random decision selection. Insert
+/// a seeded instance to make runs reproducible, by default it is seeded from entropy.
+#[derive(Resource)]
+pub struct UtilityAIRng(pub StdRng);
+
+impl UtilityAIRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Default for UtilityAIRng {
+    fn default() -> Self {
+        Self(StdRng::from_entropy())
+    }
+}
+
 #[derive(Resource)]
 pub(crate) struct UtilityAISettings {
     pub(crate) default_schedule: InternedScheduleLabel,
@@ -51,14 +70,18 @@ impl Plugin for UtilityAIPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.add_event::<UpdateEntityActionInternalEvent>()
             .add_event::<EntityActionChangedEvent>()
+            .add_event::<ActionCompletedEvent>()
             .insert_resource(UtilityAISettings {
                 default_schedule: self.schedule,
             })
             .init_resource::<AIDefinitions>()
             .init_resource::<AddedSystemTracker>()
+            .init_resource::<UtilityAIRng>()
+            .init_resource::<Time>()
             .add_systems(
                 self.schedule,
                 (
+                    complete_actions_sys.in_set(UtilityAISet::Prepare),
                     make_decisions_sys.in_set(UtilityAISet::MakeDecisions),
                     update_actions_sys.in_set(UtilityAISet::UpdateActions),
                 ),
@@ -66,8 +89,10 @@ impl Plugin for UtilityAIPlugin {
             .configure_sets(
                 self.schedule,
                 (
+                    UtilityAISet::Prepare.before(UtilityAISet::CalculateInputs),
                     UtilityAISet::CalculateInputs.before(UtilityAISet::MakeDecisions),
                     UtilityAISet::MakeDecisions.before(UtilityAISet::UpdateActions),
+                    UtilityAISet::UpdateActions.before(UtilityAISet::Tidyup),
                 ),
             );
 
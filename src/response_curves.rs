@@ -1,17 +1,136 @@
-use std::fmt::{Debug, Display, Formatter};
+use std::{
+    any::Any,
+    fmt::{Debug, Display, Formatter},
+    sync::Arc,
+};
 
 /// The trait for implementing a Response Curve which transforms an arbitrary input.
 /// Note that the output of transform will be capped between 0.0 and 1.0 by the framework.
+/// Custom implementations can be used via [`ResponseCurve::custom`].
 pub trait InputTransform: Send + Sync + PartialEq {
     fn transform(&self, input: f32) -> f32;
 }
 
 #[derive(PartialEq, Clone)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
 pub enum ResponseCurve {
     LinearCurve(Linear),
     PolynomialCurve(Polynomial),
     LogisticCurve(Logistic),
     PiecewiseLinear(PiecewiseLinear),
+    ExponentialDecayCurve(ExponentialDecay),
+    LogitCurve(Logit),
+    NormalCurve(Normal),
+    SineCurve(Sine),
+    CosineCurve(Cosine),
+    SmoothstepCurve(Smoothstep),
+    StepCurve(Step),
+    CubicBezierCurve(CubicBezier),
+    Combinator(Box<Combinator>),
+    #[cfg_attr(feature = "assets", serde(skip_deserializing))]
+    Custom(CustomCurve),
+}
+
+impl ResponseCurve {
+    /// Wraps a user defined [`InputTransform`] so that it can be used as a Response Curve,
+    /// the name is used when displaying the curve.
+    pub fn custom(name: impl Into<String>, curve: impl InputTransform + 'static) -> Self {
+        Self::Custom(CustomCurve {
+            name: name.into(),
+            curve: Arc::new(curve),
+        })
+    }
+
+    fn combine(combinator: Combinator) -> Self {
+        Self::Combinator(Box::new(combinator))
+    }
+
+    /// Checks the parameters that the curve constructors would otherwise panic on, this is
+    /// used for curves that were deserialized or may not have been built by a constructor.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        match self {
+            ResponseCurve::PiecewiseLinear(x) => x.validate(),
+            ResponseCurve::NormalCurve(x) => x.validate(),
+            ResponseCurve::SmoothstepCurve(x) => x.validate(),
+            ResponseCurve::CubicBezierCurve(x) => x.validate(),
+            ResponseCurve::Combinator(x) => x.validate(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns a curve which is `1 - y` of this curve.
+    pub fn invert(self) -> Self {
+        Self::combine(Combinator::Invert(self))
+    }
+
+    /// Returns a curve which is this curve mirrored around x = 0.5, i.e. `f(1 - x)`.
+    pub fn mirror_x(self) -> Self {
+        Self::combine(Combinator::MirrorX(self))
+    }
+
+    /// Returns a curve which clamps its input between min and max before passing it to
+    /// this curve.
+    ///
+    /// # Panics
+    ///
+    /// Panics if min is greater than max or either is NaN.
+    pub fn clamp_input(self, min: f32, max: f32) -> Self {
+        if min.is_nan() || max.is_nan() || min > max {
+            panic!("min must not be greater than max")
+        }
+        Self::combine(Combinator::ClampInput {
+            curve: self,
+            min,
+            max,
+        })
+    }
+
+    /// Returns a curve which maps its input from min..max onto 0..1 before passing it to
+    /// this curve. The input is not clamped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if min is not less than max.
+    pub fn normalize_input(self, min: f32, max: f32) -> Self {
+        if min >= max {
+            panic!("min must be less than max")
+        }
+        Self::combine(Combinator::NormalizeInput {
+            curve: self,
+            min,
+            max,
+        })
+    }
+
+    /// Returns a curve which multiplies the output of this curve by the given factor.
+    pub fn scale(self, factor: f32) -> Self {
+        Self::combine(Combinator::Scale(self, factor))
+    }
+
+    /// Returns a curve which adds the given offset to the output of this curve.
+    pub fn offset(self, offset: f32) -> Self {
+        Self::combine(Combinator::Offset(self, offset))
+    }
+
+    /// Returns a curve which is the product of this curve and the other.
+    pub fn multiply(self, other: impl Into<ResponseCurve>) -> Self {
+        Self::combine(Combinator::Multiply(self, other.into()))
+    }
+
+    /// Returns a curve which is the minimum of this curve and the other.
+    pub fn min(self, other: impl Into<ResponseCurve>) -> Self {
+        Self::combine(Combinator::Min(self, other.into()))
+    }
+
+    /// Returns a curve which is the maximum of this curve and the other.
+    pub fn max(self, other: impl Into<ResponseCurve>) -> Self {
+        Self::combine(Combinator::Max(self, other.into()))
+    }
+
+    /// Returns a curve which is the mean of this curve and the other.
+    pub fn average(self, other: impl Into<ResponseCurve>) -> Self {
+        Self::combine(Combinator::Average(self, other.into()))
+    }
 }
 
 impl InputTransform for ResponseCurve {
@@ -21,6 +140,16 @@ impl InputTransform for ResponseCurve {
             ResponseCurve::PolynomialCurve(x) => x.transform(input),
             ResponseCurve::LogisticCurve(x) => x.transform(input),
             ResponseCurve::PiecewiseLinear(x) => x.transform(input),
+            ResponseCurve::ExponentialDecayCurve(x) => x.transform(input),
+            ResponseCurve::LogitCurve(x) => x.transform(input),
+            ResponseCurve::NormalCurve(x) => x.transform(input),
+            ResponseCurve::SineCurve(x) => x.transform(input),
+            ResponseCurve::CosineCurve(x) => x.transform(input),
+            ResponseCurve::SmoothstepCurve(x) => x.transform(input),
+            ResponseCurve::StepCurve(x) => x.transform(input),
+            ResponseCurve::CubicBezierCurve(x) => x.transform(input),
+            ResponseCurve::Combinator(x) => x.transform(input),
+            ResponseCurve::Custom(x) => x.transform(input),
         }
     }
 }
@@ -101,6 +230,98 @@ impl Display for ResponseCurve {
                 write!(f, "{:?}", r.points)?;
                 write!(f, ")")
             }
+            ResponseCurve::ExponentialDecayCurve(r) => {
+                write!(f, "ExpDecay(e^(")?;
+                write_slope(f, -r.k)?;
+                write_x(f, r.x_shift)?;
+                write!(f, ")")?;
+                write_y_shift(f, r.y_shift)?;
+                write!(f, ")")
+            }
+            ResponseCurve::LogitCurve(r) => {
+                write!(f, "Logit(")?;
+                write_slope(f, r.slope)?;
+                write!(f, "ln(")?;
+                write_x(f, r.x_shift)?;
+                write!(f, " / (1 - ")?;
+                write_x(f, r.x_shift)?;
+                write!(f, "))")?;
+                write_y_shift(f, r.y_shift)?;
+                write!(f, ")")
+            }
+            ResponseCurve::NormalCurve(r) => {
+                write!(f, "Normal(mean=")?;
+                write_float(f, r.mean)?;
+                write!(f, ",std_dev=")?;
+                write_float(f, r.std_dev)?;
+                write!(f, ")")
+            }
+            ResponseCurve::SineCurve(r) => {
+                write!(f, "Sine(")?;
+                write_slope(f, r.amplitude)?;
+                write!(f, "sin(")?;
+                write_slope(f, r.frequency)?;
+                write_x(f, r.x_shift)?;
+                write!(f, ")")?;
+                write_y_shift(f, r.y_shift)?;
+                write!(f, ")")
+            }
+            ResponseCurve::CosineCurve(r) => {
+                write!(f, "Cosine(")?;
+                write_slope(f, r.amplitude)?;
+                write!(f, "cos(")?;
+                write_slope(f, r.frequency)?;
+                write_x(f, r.x_shift)?;
+                write!(f, ")")?;
+                write_y_shift(f, r.y_shift)?;
+                write!(f, ")")
+            }
+            ResponseCurve::SmoothstepCurve(r) => {
+                write!(f, "Smoothstep(edge0=")?;
+                write_float(f, r.edge0)?;
+                write!(f, ",edge1=")?;
+                write_float(f, r.edge1)?;
+                write!(f, ")")
+            }
+            ResponseCurve::StepCurve(r) => {
+                write!(f, "Step(x >= ")?;
+                write_float(f, r.threshold)?;
+                write!(f, " ? ")?;
+                write_float(f, r.high)?;
+                write!(f, " : ")?;
+                write_float(f, r.low)?;
+                write!(f, ")")
+            }
+            ResponseCurve::CubicBezierCurve(r) => {
+                write!(f, "CubicBezier(")?;
+                write!(f, "{:.},{:.},{:.},{:.}", r.x1, r.y1, r.x2, r.y2)?;
+                write!(f, ")")
+            }
+            ResponseCurve::Combinator(r) => match r.as_ref() {
+                Combinator::Invert(c) => write!(f, "(1 - {c})"),
+                Combinator::MirrorX(c) => write!(f, "MirrorX({c})"),
+                Combinator::ClampInput { curve, min, max } => {
+                    write!(f, "ClampInput({curve}, {min:.}..{max:.})")
+                }
+                Combinator::NormalizeInput { curve, min, max } => {
+                    write!(f, "NormalizeInput({curve}, {min:.}..{max:.})")
+                }
+                Combinator::Scale(c, factor) => {
+                    write!(f, "(")?;
+                    write_float(f, *factor)?;
+                    write!(f, " * {c})")
+                }
+                Combinator::Offset(c, offset) => {
+                    write!(f, "({c}")?;
+                    write_y_shift(f, *offset)?;
+                    write!(f, ")")
+                }
+                Combinator::Multiply(a, b) => write!(f, "({a} * {b})"),
+                Combinator::Min(a, b) => write!(f, "Min({a}, {b})"),
+                Combinator::Max(a, b) => write!(f, "Max({a}, {b})"),
+                Combinator::Average(a, b) => write!(f, "Avg({a}, {b})"),
+            },
+            ResponseCurve::Custom(r) => write!(f, "{}", r.name),
         }
     }
 }
@@ -129,8 +350,57 @@ impl From<PiecewiseLinear> for ResponseCurve {
     }
 }
 
+impl From<ExponentialDecay> for ResponseCurve {
+    fn from(value: ExponentialDecay) -> Self {
+        Self::ExponentialDecayCurve(value)
+    }
+}
+
+impl From<Logit> for ResponseCurve {
+    fn from(value: Logit) -> Self {
+        Self::LogitCurve(value)
+    }
+}
+
+impl From<Normal> for ResponseCurve {
+    fn from(value: Normal) -> Self {
+        Self::NormalCurve(value)
+    }
+}
+
+impl From<Sine> for ResponseCurve {
+    fn from(value: Sine) -> Self {
+        Self::SineCurve(value)
+    }
+}
+
+impl From<Cosine> for ResponseCurve {
+    fn from(value: Cosine) -> Self {
+        Self::CosineCurve(value)
+    }
+}
+
+impl From<Smoothstep> for ResponseCurve {
+    fn from(value: Smoothstep) -> Self {
+        Self::SmoothstepCurve(value)
+    }
+}
+
+impl From<Step> for ResponseCurve {
+    fn from(value: Step) -> Self {
+        Self::StepCurve(value)
+    }
+}
+
+impl From<CubicBezier> for ResponseCurve {
+    fn from(value: CubicBezier) -> Self {
+        Self::CubicBezierCurve(value)
+    }
+}
+
 /// Implements the formula `y = slope * (x - x_shift) + y_shift`
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
 pub struct Linear {
     pub slope: f32,
     pub x_shift: f32,
@@ -162,6 +432,7 @@ impl InputTransform for Linear {
 }
 /// Implements the formula `y = slope * (x - x_shift) ^ k + y_shift`
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
 pub struct Polynomial {
     pub slope: f32,
     pub k: f32,
@@ -197,6 +468,7 @@ impl InputTransform for Polynomial {
 
 /// Implements the formula `y = (1 / (1 + k ^ - (x - x_shift))) + y_shift`
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
 pub struct Logistic {
     pub k: f32,
     pub x_shift: f32,
@@ -227,10 +499,354 @@ impl InputTransform for Logistic {
     }
 }
 
+/// Implements the formula `y = e ^ (-k * (x - x_shift)) + y_shift`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
+pub struct ExponentialDecay {
+    pub k: f32,
+    pub x_shift: f32,
+    pub y_shift: f32,
+}
+
+impl ExponentialDecay {
+    pub fn new(k: f32) -> Self {
+        Self {
+            k,
+            x_shift: 0.0,
+            y_shift: 0.0,
+        }
+    }
+
+    pub fn shifted(self, x_shift: f32, y_shift: f32) -> Self {
+        Self {
+            k: self.k,
+            x_shift,
+            y_shift,
+        }
+    }
+}
+
+impl InputTransform for ExponentialDecay {
+    fn transform(&self, input: f32) -> f32 {
+        (-self.k * (input - self.x_shift)).exp() + self.y_shift
+    }
+}
+
+/// Implements the formula `y = slope * ln((x - x_shift) / (1 - (x - x_shift))) + y_shift`,
+/// the inverse of the Logistic curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
+pub struct Logit {
+    pub slope: f32,
+    pub x_shift: f32,
+    pub y_shift: f32,
+}
+
+impl Logit {
+    pub fn new(slope: f32) -> Self {
+        Self {
+            slope,
+            x_shift: 0.0,
+            y_shift: 0.0,
+        }
+    }
+
+    pub fn shifted(self, x_shift: f32, y_shift: f32) -> Self {
+        Self {
+            slope: self.slope,
+            x_shift,
+            y_shift,
+        }
+    }
+}
+
+impl InputTransform for Logit {
+    fn transform(&self, input: f32) -> f32 {
+        let x = input - self.x_shift;
+        self.slope * (x / (1.0 - x)).ln() + self.y_shift
+    }
+}
+
+/// Implements the bell curve `y = e ^ (-(x - mean) ^ 2 / (2 * std_dev ^ 2))`, this peaks at
+/// 1.0 when x is the mean.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
+pub struct Normal {
+    pub mean: f32,
+    pub std_dev: f32,
+}
+
+impl Normal {
+    /// Creates a new [`Normal`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mean or std_dev are not finite or the std_dev is not greater than 0.0.
+    pub fn new(mean: f32, std_dev: f32) -> Self {
+        let curve = Self { mean, std_dev };
+        if let Err(reason) = curve.validate() {
+            panic!("{reason}")
+        }
+        curve
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !self.mean.is_finite() || !self.std_dev.is_finite() {
+            return Err("mean and std_dev must be finite".to_string());
+        }
+        if self.std_dev <= 0.0 {
+            return Err("std_dev must be greater than 0.0".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl InputTransform for Normal {
+    fn transform(&self, input: f32) -> f32 {
+        (-(input - self.mean).powi(2) / (2.0 * self.std_dev.powi(2))).exp()
+    }
+}
+
+/// Implements the formula `y = amplitude * sin(frequency * (x - x_shift)) + y_shift`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
+pub struct Sine {
+    pub amplitude: f32,
+    pub frequency: f32,
+    pub x_shift: f32,
+    pub y_shift: f32,
+}
+
+impl Sine {
+    pub fn new(amplitude: f32, frequency: f32) -> Self {
+        Self {
+            amplitude,
+            frequency,
+            x_shift: 0.0,
+            y_shift: 0.0,
+        }
+    }
+
+    pub fn shifted(self, x_shift: f32, y_shift: f32) -> Self {
+        Self {
+            amplitude: self.amplitude,
+            frequency: self.frequency,
+            x_shift,
+            y_shift,
+        }
+    }
+}
+
+impl InputTransform for Sine {
+    fn transform(&self, input: f32) -> f32 {
+        self.amplitude * (self.frequency * (input - self.x_shift)).sin() + self.y_shift
+    }
+}
+
+/// Implements the formula `y = amplitude * cos(frequency * (x - x_shift)) + y_shift`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
+pub struct Cosine {
+    pub amplitude: f32,
+    pub frequency: f32,
+    pub x_shift: f32,
+    pub y_shift: f32,
+}
+
+impl Cosine {
+    pub fn new(amplitude: f32, frequency: f32) -> Self {
+        Self {
+            amplitude,
+            frequency,
+            x_shift: 0.0,
+            y_shift: 0.0,
+        }
+    }
+
+    pub fn shifted(self, x_shift: f32, y_shift: f32) -> Self {
+        Self {
+            amplitude: self.amplitude,
+            frequency: self.frequency,
+            x_shift,
+            y_shift,
+        }
+    }
+}
+
+impl InputTransform for Cosine {
+    fn transform(&self, input: f32) -> f32 {
+        self.amplitude * (self.frequency * (input - self.x_shift)).cos() + self.y_shift
+    }
+}
+
+/// Implements the formula `y = t^2 * (3 - 2t)` where
+/// `t = clamp((x - edge0) / (edge1 - edge0), 0, 1)`. This eases from 0.0 at edge0 to 1.0 at
+/// edge1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
+pub struct Smoothstep {
+    pub edge0: f32,
+    pub edge1: f32,
+}
+
+impl Smoothstep {
+    /// Creates a new [`Smoothstep`], edge0 may be greater than edge1 to create a curve that
+    /// eases from 1.0 to 0.0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if edge0 and edge1 are equal.
+    pub fn new(edge0: f32, edge1: f32) -> Self {
+        let curve = Self { edge0, edge1 };
+        if let Err(reason) = curve.validate() {
+            panic!("{reason}")
+        }
+        curve
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.edge0 == self.edge1 {
+            return Err("edge0 and edge1 must not be equal".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl InputTransform for Smoothstep {
+    fn transform(&self, input: f32) -> f32 {
+        let t = ((input - self.edge0) / (self.edge1 - self.edge0)).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+/// Returns `high` when x is greater than or equal to the threshold, otherwise `low`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
+pub struct Step {
+    pub threshold: f32,
+    pub low: f32,
+    pub high: f32,
+}
+
+impl Step {
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            threshold,
+            low: 0.0,
+            high: 1.0,
+        }
+    }
+
+    pub fn with_values(self, low: f32, high: f32) -> Self {
+        Self {
+            threshold: self.threshold,
+            low,
+            high,
+        }
+    }
+}
+
+impl InputTransform for Step {
+    fn transform(&self, input: f32) -> f32 {
+        if input >= self.threshold {
+            self.high
+        } else {
+            self.low
+        }
+    }
+}
+
+/// A cubic Bezier curve from (0, 0) to (1, 1) with the two given control points, the same as
+/// CSS's `cubic-bezier` easing functions. Inputs are clamped between 0.0 and 1.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
+pub struct CubicBezier {
+    pub x1: f32,
+    pub y1: f32,
+    pub x2: f32,
+    pub y2: f32,
+}
+
+impl CubicBezier {
+    /// Creates a new [`CubicBezier`] with the control points (x1, y1) and (x2, y2).
+    ///
+    /// # Panics
+    ///
+    /// Panics if x1 or x2 are not between 0.0 and 1.0, as the curve would not be a function
+    /// of x.
+    pub fn new(x1: f32, y1: f32, x2: f32, y2: f32) -> Self {
+        let curve = Self { x1, y1, x2, y2 };
+        if let Err(reason) = curve.validate() {
+            panic!("{reason}")
+        }
+        curve
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.x1) || !(0.0..=1.0).contains(&self.x2) {
+            return Err(
+                "The x coordinates of the control points must be between 0.0 and 1.0"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    fn bezier(t: f32, p1: f32, p2: f32) -> f32 {
+        let u = 1.0 - t;
+        3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t
+    }
+
+    fn bezier_derivative(t: f32, p1: f32, p2: f32) -> f32 {
+        let u = 1.0 - t;
+        3.0 * u * u * p1 + 6.0 * u * t * (p2 - p1) + 3.0 * t * t * (1.0 - p2)
+    }
+
+    /// Finds t such that x(t) = x, using Newton's method and falling back to bisection.
+    fn solve_t(&self, x: f32) -> f32 {
+        let mut t = x;
+        for _ in 0..8 {
+            let error = Self::bezier(t, self.x1, self.x2) - x;
+            if error.abs() < 1e-6 {
+                return t;
+            }
+            let derivative = Self::bezier_derivative(t, self.x1, self.x2);
+            if derivative.abs() < 1e-6 {
+                break;
+            }
+            t -= error / derivative;
+        }
+
+        let (mut lower, mut upper) = (0.0, 1.0);
+        t = x;
+        for _ in 0..32 {
+            let value = Self::bezier(t, self.x1, self.x2);
+            if (value - x).abs() < 1e-6 {
+                break;
+            }
+            if value < x {
+                lower = t;
+            } else {
+                upper = t;
+            }
+            t = (lower + upper) / 2.0;
+        }
+        t
+    }
+}
+
+impl InputTransform for CubicBezier {
+    fn transform(&self, input: f32) -> f32 {
+        let t = self.solve_t(input.clamp(0.0, 1.0));
+        Self::bezier(t, self.y1, self.y2)
+    }
+}
+
 /// Custom curve defined by linear interpolation between the set of given points.
 /// Out of bounds points to the left and right return the y value of the first or last
 /// point respectively.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
 pub struct PiecewiseLinear {
     points: Vec<(f32, f32)>,
 }
@@ -243,22 +859,32 @@ impl PiecewiseLinear {
     /// Panics if the iterator provides less than two points or the points are not
     /// strictly monotonically increasing in their x coordinates.
     pub fn new(points: impl IntoIterator<Item = (f32, f32)>) -> Self {
-        let points = Vec::from_iter(points);
-        if points.len() < 2 {
-            panic!("You must provide at least two points to the PiecewiseLinear")
+        let curve = Self {
+            points: Vec::from_iter(points),
+        };
+        if let Err(reason) = curve.validate() {
+            panic!("{reason}")
+        }
+        curve
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.points.len() < 2 {
+            return Err(
+                "You must provide at least two points to the PiecewiseLinear".to_string(),
+            );
         }
-        let prev_x = f32::NEG_INFINITY;
-        for (x, _) in &points {
+        let mut prev_x = f32::NEG_INFINITY;
+        for (x, _) in &self.points {
             if *x <= prev_x {
-                panic!(
+                return Err(format!(
                     "Expected points which are strictly monotonically increasing in x. \
                     However, {x} is not greater than {prev_x}"
-                )
+                ));
             }
+            prev_x = *x;
         }
-        Self {
-            points: Vec::from_iter(points),
-        }
+        Ok(())
     }
 }
 
@@ -290,9 +916,149 @@ impl InputTransform for PiecewiseLinear {
     }
 }
 
+/// A Response Curve built from other curves, see the combinator methods on
+/// [`ResponseCurve`] such as [`ResponseCurve::invert`] and [`ResponseCurve::multiply`].
+#[derive(PartialEq, Clone)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
+pub enum Combinator {
+    Invert(ResponseCurve),
+    MirrorX(ResponseCurve),
+    ClampInput {
+        curve: ResponseCurve,
+        min: f32,
+        max: f32,
+    },
+    NormalizeInput {
+        curve: ResponseCurve,
+        min: f32,
+        max: f32,
+    },
+    Scale(ResponseCurve, f32),
+    Offset(ResponseCurve, f32),
+    Multiply(ResponseCurve, ResponseCurve),
+    Min(ResponseCurve, ResponseCurve),
+    Max(ResponseCurve, ResponseCurve),
+    Average(ResponseCurve, ResponseCurve),
+}
+
+impl Combinator {
+    /// Checks the combinator and the curves it combines, see [`ResponseCurve::validate`].
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            Combinator::ClampInput { min, max, .. }
+                if min.is_nan() || max.is_nan() || min > max =>
+            {
+                Err("min must not be greater than max".to_string())
+            }
+            Combinator::NormalizeInput { min, max, .. } if min >= max => {
+                Err("min must be less than max".to_string())
+            }
+            Combinator::Invert(c)
+            | Combinator::MirrorX(c)
+            | Combinator::ClampInput { curve: c, .. }
+            | Combinator::NormalizeInput { curve: c, .. }
+            | Combinator::Scale(c, _)
+            | Combinator::Offset(c, _) => c.validate(),
+            Combinator::Multiply(a, b)
+            | Combinator::Min(a, b)
+            | Combinator::Max(a, b)
+            | Combinator::Average(a, b) => a.validate().and_then(|_| b.validate()),
+        }
+    }
+}
+
+impl InputTransform for Combinator {
+    fn transform(&self, input: f32) -> f32 {
+        match self {
+            Combinator::Invert(c) => 1.0 - c.transform(input),
+            Combinator::MirrorX(c) => c.transform(1.0 - input),
+            Combinator::ClampInput { curve, min, max } => {
+                curve.transform(input.clamp(*min, *max))
+            }
+            Combinator::NormalizeInput { curve, min, max } => {
+                curve.transform((input - min) / (max - min))
+            }
+            Combinator::Scale(c, factor) => factor * c.transform(input),
+            Combinator::Offset(c, offset) => c.transform(input) + offset,
+            Combinator::Multiply(a, b) => a.transform(input) * b.transform(input),
+            Combinator::Min(a, b) => a.transform(input).min(b.transform(input)),
+            Combinator::Max(a, b) => a.transform(input).max(b.transform(input)),
+            Combinator::Average(a, b) => (a.transform(input) + b.transform(input)) / 2.0,
+        }
+    }
+}
+
+/// A user defined Response Curve, see [`ResponseCurve::custom`]. Two custom curves are
+/// equal if they share the same name and their transforms are of the same type and equal.
+#[derive(Clone)]
+pub struct CustomCurve {
+    pub name: String,
+    curve: Arc<dyn DynInputTransform>,
+}
+
+impl PartialEq for CustomCurve {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.curve.dyn_eq(other.curve.as_any())
+    }
+}
+
+/// An object safe version of [`InputTransform`], as its `PartialEq` bound prevents it from
+/// being used as a trait object.
+trait DynInputTransform: Send + Sync {
+    fn dyn_transform(&self, input: f32) -> f32;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn dyn_eq(&self, other: &dyn Any) -> bool;
+}
+
+impl<T: InputTransform + 'static> DynInputTransform for T {
+    fn dyn_transform(&self, input: f32) -> f32 {
+        self.transform(input)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn dyn_eq(&self, other: &dyn Any) -> bool {
+        other.downcast_ref::<T>().is_some_and(|other| self == other)
+    }
+}
+
+impl InputTransform for CustomCurve {
+    fn transform(&self, input: f32) -> f32 {
+        self.curve.dyn_transform(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::response_curves::{InputTransform, PiecewiseLinear};
+    use crate::response_curves::{
+        Cosine, CubicBezier, ExponentialDecay, InputTransform, Linear, Logit, Normal,
+        PiecewiseLinear, Polynomial, ResponseCurve, Sine, Smoothstep, Step,
+    };
+    use std::f32::consts::PI;
+
+    #[derive(PartialEq)]
+    struct Threshold(f32);
+
+    impl InputTransform for Threshold {
+        fn transform(&self, input: f32) -> f32 {
+            (input >= self.0) as u8 as f32
+        }
+    }
+
+    #[test]
+    fn test_custom_curve() {
+        let curve = ResponseCurve::custom("Threshold(0.5)", Threshold(0.5));
+        assert_eq!(curve.transform(0.4), 0.0);
+        assert_eq!(curve.transform(0.6), 1.0);
+        assert_eq!(curve.to_string(), "Threshold(0.5)");
+        assert!(curve == curve.clone());
+        assert!(curve == ResponseCurve::custom("Threshold(0.5)", Threshold(0.5)));
+        assert!(curve != ResponseCurve::custom("Threshold(0.5)", Threshold(0.6)));
+    }
 
     #[test]
     fn test_piecewise_linear() {
@@ -330,4 +1096,196 @@ mod tests {
         let piece_wise_linear = PiecewiseLinear::new(vec![(0.0, 0.0), (1.0, 1.0)]);
         assert_eq!(piece_wise_linear.transform(1.1), 1.0);
     }
+
+    fn assert_approx_eq(a: f32, b: f32) {
+        assert!((a - b).abs() < 0.001, "{a} != {b}");
+    }
+
+    #[test]
+    fn test_exponential_decay() {
+        let curve = ExponentialDecay::new(2.0);
+        assert_approx_eq(curve.transform(0.0), 1.0);
+        assert_approx_eq(curve.transform(0.5), 0.3679);
+        assert_approx_eq(curve.shifted(0.5, 0.1).transform(0.5), 1.1);
+        assert_eq!(
+            ResponseCurve::from(curve.shifted(0.5, 0.1)).to_string(),
+            "ExpDecay(e^(-2(x - 0.5)) + 0.1)"
+        );
+        assert_eq!(
+            ResponseCurve::from(ExponentialDecay::new(-2.0)).to_string(),
+            "ExpDecay(e^(2x))"
+        );
+    }
+
+    #[test]
+    fn test_logit() {
+        let curve = Logit::new(0.1).shifted(0.0, 0.5);
+        assert_approx_eq(curve.transform(0.5), 0.5);
+        assert!(curve.transform(0.1) < 0.5);
+        assert!(curve.transform(0.9) > 0.5);
+        assert_approx_eq(curve.transform(0.9) - 0.5, 0.5 - curve.transform(0.1));
+        assert_eq!(
+            ResponseCurve::from(curve).to_string(),
+            "Logit(0.1ln(x / (1 - x)) + 0.5)"
+        );
+    }
+
+    #[test]
+    fn test_normal() {
+        let curve = Normal::new(0.5, 0.1);
+        assert_approx_eq(curve.transform(0.5), 1.0);
+        assert_approx_eq(curve.transform(0.6), 0.6065);
+        assert_approx_eq(curve.transform(0.4), curve.transform(0.6));
+        assert_eq!(
+            ResponseCurve::from(curve).to_string(),
+            "Normal(mean=0.5,std_dev=0.1)"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_normal_invalid_std_dev() {
+        Normal::new(0.5, 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_normal_nan_std_dev() {
+        Normal::new(0.5, f32::NAN);
+    }
+
+    #[test]
+    fn test_sine() {
+        let curve = Sine::new(0.5, PI).shifted(0.0, 0.5);
+        assert_approx_eq(curve.transform(0.0), 0.5);
+        assert_approx_eq(curve.transform(0.5), 1.0);
+        assert_eq!(
+            ResponseCurve::from(Sine::new(1.0, 2.0)).to_string(),
+            "Sine(sin(2x))"
+        );
+    }
+
+    #[test]
+    fn test_cosine() {
+        let curve = Cosine::new(0.5, PI).shifted(0.0, 0.5);
+        assert_approx_eq(curve.transform(0.0), 1.0);
+        assert_approx_eq(curve.transform(0.5), 0.5);
+        assert_approx_eq(curve.transform(1.0), 0.0);
+        assert_eq!(
+            ResponseCurve::from(Cosine::new(1.0, 2.0).shifted(1.0, 0.0)).to_string(),
+            "Cosine(cos(2(x - 1)))"
+        );
+    }
+
+    #[test]
+    fn test_smoothstep() {
+        let curve = Smoothstep::new(0.2, 0.8);
+        assert_approx_eq(curve.transform(0.0), 0.0);
+        assert_approx_eq(curve.transform(0.2), 0.0);
+        assert_approx_eq(curve.transform(0.5), 0.5);
+        assert_approx_eq(curve.transform(0.8), 1.0);
+        assert_approx_eq(curve.transform(1.0), 1.0);
+        assert_approx_eq(Smoothstep::new(1.0, 0.0).transform(0.0), 1.0);
+        assert_eq!(
+            ResponseCurve::from(curve).to_string(),
+            "Smoothstep(edge0=0.2,edge1=0.8)"
+        );
+    }
+
+    #[test]
+    fn test_step() {
+        let curve = Step::new(0.5);
+        assert_eq!(curve.transform(0.49), 0.0);
+        assert_eq!(curve.transform(0.5), 1.0);
+        let curve = curve.with_values(0.2, 0.8);
+        assert_eq!(curve.transform(0.0), 0.2);
+        assert_eq!(curve.transform(1.0), 0.8);
+        assert_eq!(
+            ResponseCurve::from(curve).to_string(),
+            "Step(x >= 0.5 ? 0.8 : 0.2)"
+        );
+    }
+
+    #[test]
+    fn test_cubic_bezier() {
+        // control points on the diagonal give a straight line
+        let linear = CubicBezier::new(0.25, 0.25, 0.75, 0.75);
+        for x in [0.0, 0.1, 0.3, 0.5, 0.7, 0.9, 1.0] {
+            assert_approx_eq(linear.transform(x), x);
+        }
+
+        // ease-in-out is symmetric around the midpoint
+        let ease_in_out = CubicBezier::new(0.42, 0.0, 0.58, 1.0);
+        assert_approx_eq(ease_in_out.transform(0.5), 0.5);
+        assert!(ease_in_out.transform(0.2) < 0.2);
+        assert_approx_eq(ease_in_out.transform(0.2), 1.0 - ease_in_out.transform(0.8));
+
+        // out of bounds inputs are clamped
+        assert_approx_eq(ease_in_out.transform(-1.0), 0.0);
+        assert_approx_eq(ease_in_out.transform(2.0), 1.0);
+
+        assert_eq!(
+            ResponseCurve::from(ease_in_out).to_string(),
+            "CubicBezier(0.42,0,0.58,1)"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cubic_bezier_invalid_control_point() {
+        CubicBezier::new(1.5, 0.0, 0.5, 1.0);
+    }
+
+    #[test]
+    fn test_combinators() {
+        let linear = ResponseCurve::from(Linear::new(1.0));
+        let squared = ResponseCurve::from(Polynomial::new(1.0, 2.0));
+
+        let inverted = linear.clone().invert();
+        assert_approx_eq(inverted.transform(0.25), 0.75);
+        assert_eq!(inverted.to_string(), "(1 - Linear(x))");
+
+        let mirrored = squared.clone().mirror_x();
+        assert_approx_eq(mirrored.transform(0.25), 0.5625);
+        assert_eq!(mirrored.to_string(), "MirrorX(Poly(x^2))");
+
+        let clamped = linear.clone().clamp_input(0.2, 0.8);
+        assert_approx_eq(clamped.transform(0.0), 0.2);
+        assert_approx_eq(clamped.transform(0.5), 0.5);
+        assert_approx_eq(clamped.transform(1.0), 0.8);
+        assert_eq!(clamped.to_string(), "ClampInput(Linear(x), 0.2..0.8)");
+
+        let normalized = linear.clone().normalize_input(0.0, 10.0);
+        assert_approx_eq(normalized.transform(5.0), 0.5);
+        assert_eq!(normalized.to_string(), "NormalizeInput(Linear(x), 0..10)");
+
+        let scaled = linear.clone().scale(0.5).offset(0.25);
+        assert_approx_eq(scaled.transform(1.0), 0.75);
+        assert_eq!(scaled.to_string(), "((0.5 * Linear(x)) + 0.25)");
+
+        assert_approx_eq(
+            linear.clone().multiply(squared.clone()).transform(0.5),
+            0.125,
+        );
+        assert_approx_eq(linear.clone().min(squared.clone()).transform(0.5), 0.25);
+        assert_approx_eq(linear.clone().max(squared.clone()).transform(0.5), 0.5);
+        assert_approx_eq(
+            linear.clone().average(squared.clone()).transform(0.5),
+            0.375,
+        );
+        assert_eq!(
+            linear.clone().multiply(squared.clone()).to_string(),
+            "(Linear(x) * Poly(x^2))"
+        );
+        assert_eq!(
+            linear.average(squared.invert()).to_string(),
+            "Avg(Linear(x), (1 - Poly(x^2)))"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_normalize_input_invalid_range() {
+        ResponseCurve::from(Linear::new(1.0)).normalize_input(1.0, 1.0);
+    }
 }
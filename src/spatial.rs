@@ -0,0 +1,142 @@
+use crate::plugin::{UtilityAISet, UtilityAISettings};
+use bevy::{
+    app::{App, Plugin, Update},
+    ecs::schedule::ScheduleLabel,
+    math::{IVec3, Vec3},
+    prelude::{Entity, IntoSystemConfigs, Query, ResMut, Resource, Transform},
+    utils::HashMap,
+};
+
+/// A spatial index which can be used to limit the targets considered by a targeted input
+/// system, see the `spatial_index` argument of `#[targeted_input_system]`.
+pub trait SpatialIndex: Resource {
+    /// Returns the entities whose position is within range of the given position.
+    fn entities_within(&self, position: Vec3, range: f32) -> Vec<Entity>;
+}
+
+/// The default [`SpatialIndex`], a uniform grid of every entity with a [`Transform`] which
+/// is rebuilt each frame. Add the [`TransformGridPlugin`] to use it.
+#[derive(Resource)]
+pub struct TransformGrid {
+    cell_size: f32,
+    cells: HashMap<IVec3, Vec<(Entity, Vec3)>>,
+    /// The min & max occupied cell on each axis, so that queries don't have to visit cells
+    /// that can't contain anything, e.g. on the z axis in 2D games.
+    bounds: Option<(IVec3, IVec3)>,
+}
+
+impl TransformGrid {
+    /// Creates a new [`TransformGrid`], ideally the cell_size is close to the max_range of
+    /// the targeted input systems that use it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell_size is not greater than 0.0.
+    pub fn new(cell_size: f32) -> Self {
+        if cell_size <= 0.0 {
+            panic!("cell_size must be greater than 0.0");
+        }
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    fn cell(&self, position: Vec3) -> IVec3 {
+        (position / self.cell_size).floor().as_ivec3()
+    }
+
+    /// Removes all entities from the grid.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.bounds = None;
+    }
+
+    /// Adds an entity at the given position to the grid.
+    pub fn insert(&mut self, entity: Entity, position: Vec3) {
+        let cell = self.cell(position);
+        self.cells.entry(cell).or_default().push((entity, position));
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (min.min(cell), max.max(cell)),
+            None => (cell, cell),
+        });
+    }
+}
+
+impl SpatialIndex for TransformGrid {
+    fn entities_within(&self, position: Vec3, range: f32) -> Vec<Entity> {
+        let Some((bounds_min, bounds_max)) = self.bounds else {
+            return Vec::new();
+        };
+        let range_squared = range * range;
+        let min = self.cell(position - range).max(bounds_min);
+        let max = self.cell(position + range).min(bounds_max);
+        let mut entities = Vec::new();
+
+        let mut visit = |cell: &Vec<(Entity, Vec3)>| {
+            for (entity, entity_position) in cell {
+                if entity_position.distance_squared(position) <= range_squared {
+                    entities.push(*entity);
+                }
+            }
+        };
+
+        // for large ranges it is quicker to visit every occupied cell
+        let cell_count = (max - min + IVec3::ONE)
+            .max(IVec3::ZERO)
+            .as_vec3()
+            .element_product();
+        if cell_count as usize > self.cells.len() {
+            self.cells.values().for_each(visit);
+        } else {
+            for x in min.x..=max.x {
+                for y in min.y..=max.y {
+                    for z in min.z..=max.z {
+                        if let Some(cell) = self.cells.get(&IVec3::new(x, y, z)) {
+                            visit(cell);
+                        }
+                    }
+                }
+            }
+        }
+
+        entities
+    }
+}
+
+/// Adds the [`TransformGrid`] resource, rebuilding it at the start of each AI update.
+pub struct TransformGridPlugin {
+    cell_size: f32,
+}
+
+impl TransformGridPlugin {
+    pub fn new(cell_size: f32) -> Self {
+        Self { cell_size }
+    }
+}
+
+impl Plugin for TransformGridPlugin {
+    fn build(&self, app: &mut App) {
+        let schedule = app
+            .world()
+            .get_resource::<UtilityAISettings>()
+            .map_or(Update.intern(), |settings| settings.default_schedule);
+
+        app.insert_resource(TransformGrid::new(self.cell_size))
+            .add_systems(
+                schedule,
+                update_transform_grid_sys.in_set(UtilityAISet::Prepare),
+            );
+    }
+}
+
+fn update_transform_grid_sys(
+    mut grid: ResMut<TransformGrid>,
+    query: Query<(Entity, &Transform)>,
+) {
+    grid.clear();
+    for (entity, transform) in &query {
+        grid.insert(entity, transform.translation);
+    }
+}
@@ -1,17 +1,30 @@
+pub mod budget;
 pub mod make_decisions;
 pub mod update_action;
 
-use bevy::prelude::{Added, Commands, Component, Entity, Query, RemovedComponents};
+use std::any::TypeId;
+
+use bevy::prelude::{
+    Added, Commands, Component, Entity, Query, RemovedComponents, World,
+};
 
 use crate::ai_meta::AIMeta;
+use crate::systems::update_action::end_entity_actions;
 
 pub(crate) fn ensure_entity_has_ai_meta<T: Component>(
     mut commmands: Commands,
     query: Query<(Entity, Option<&AIMeta>), Added<T>>,
 ) {
     for (entity, ai_meta) in &query {
-        if ai_meta.is_none() {
-            commmands.entity(entity).insert(AIMeta::new::<T>());
+        match ai_meta {
+            None => {
+                commmands.entity(entity).insert(AIMeta::new::<T>());
+            }
+            // the entity has swapped to this AI from another, which has to stop first
+            Some(ai_meta) if ai_meta.ai_definition != TypeId::of::<T>() => {
+                commmands.add(move |world: &mut World| swap_ai::<T>(world, entity));
+            }
+            Some(_) => {}
         }
     }
 }
@@ -21,6 +34,41 @@ pub(crate) fn handle_ai_marker_removed<T: Component>(
     mut removals: RemovedComponents<T>,
 ) {
     for entity in removals.read() {
-        commmands.entity(entity).remove::<AIMeta>();
+        commmands.add(move |world: &mut World| remove_ai::<T>(world, entity));
+    }
+}
+
+/// Replaces the entity's AIMeta with one for `T`, ending the actions of the previous AI.
+fn swap_ai<T: Component>(world: &mut World, entity: Entity) {
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    let ai_definition = entity_mut
+        .get::<AIMeta>()
+        .map(|ai_meta| ai_meta.ai_definition);
+    if !entity_mut.contains::<T>()
+        || ai_definition.is_none()
+        || ai_definition == Some(TypeId::of::<T>())
+    {
+        return;
+    }
+    let previous = entity_mut.take::<AIMeta>().unwrap();
+    entity_mut.insert(AIMeta::new::<T>());
+    end_entity_actions(world, entity, previous);
+}
+
+/// Removes the entity's AIMeta and ends its actions, unless the entity has since swapped
+/// to another AI or had the marker re-added.
+fn remove_ai<T: Component>(world: &mut World, entity: Entity) {
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    let ai_definition = entity_mut
+        .get::<AIMeta>()
+        .map(|ai_meta| ai_meta.ai_definition);
+    if entity_mut.contains::<T>() || ai_definition != Some(TypeId::of::<T>()) {
+        return;
     }
+    let ai_meta = entity_mut.take::<AIMeta>().unwrap();
+    end_entity_actions(world, entity, ai_meta);
 }
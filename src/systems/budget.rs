@@ -0,0 +1,77 @@
+use crate::{AIDefinitions, AIMeta};
+use bevy::prelude::{Component, Entity, Local, Query, Res, Time, With};
+
+/// Limits how often the entities of an AI are evaluated, which can be used to spread the cost
+/// of large crowds over multiple frames. Entities that are not evaluated in a frame keep their
+/// current action, and their inputs are not calculated.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "assets", derive(serde::Deserialize))]
+pub enum UpdateBudget {
+    /// Evaluate every entity every frame.
+    #[default]
+    EveryFrame,
+    /// Evaluate at most this many entities per frame, in round-robin order.
+    MaxEntitiesPerFrame(usize),
+    /// Evaluate each entity at most once per this many seconds.
+    Interval(f32),
+}
+
+/// Flags which entities of the AI should be evaluated this frame according to the AI's
+/// [`UpdateBudget`].
+pub(crate) fn schedule_evaluation_sys<T: Component>(
+    mut query: Query<(Entity, &mut AIMeta), With<T>>,
+    ai_definitions: Res<AIDefinitions>,
+    time: Res<Time>,
+    mut last_evaluated_entity: Local<Option<Entity>>,
+) {
+    let mut entities = Vec::new();
+
+    for (entity, mut ai_meta) in query.iter_mut() {
+        let Some(ai_definition) = ai_definitions.map.get(&ai_meta.ai_definition) else {
+            continue;
+        };
+        match ai_definition.update_budget {
+            UpdateBudget::EveryFrame => ai_meta.evaluate_this_frame = true,
+            UpdateBudget::MaxEntitiesPerFrame(max_entities) => {
+                ai_meta.evaluate_this_frame = false;
+                entities.push((entity, max_entities));
+            }
+            UpdateBudget::Interval(interval) => {
+                let elapsed = time.elapsed_seconds();
+                ai_meta.evaluate_this_frame = !matches!(
+                    ai_meta.last_evaluated,
+                    Some(last_evaluated) if elapsed - last_evaluated < interval
+                );
+                if ai_meta.evaluate_this_frame {
+                    ai_meta.last_evaluated = Some(elapsed);
+                }
+            }
+        }
+    }
+
+    let Some(&(_, max_entities)) = entities.first() else {
+        return;
+    };
+
+    // continue from the entity after the last one evaluated, wrapping around to the start
+    entities.sort_by_key(|(entity, _)| *entity);
+    let start = match *last_evaluated_entity {
+        Some(last) => entities.partition_point(|(entity, _)| *entity <= last),
+        None => 0,
+    };
+    let selected = Vec::from_iter(
+        entities
+            .iter()
+            .cycle()
+            .skip(start)
+            .take(max_entities.min(entities.len()))
+            .map(|(entity, _)| *entity),
+    );
+
+    for &entity in &selected {
+        let (_, mut ai_meta) = query.get_mut(entity).unwrap();
+        ai_meta.evaluate_this_frame = true;
+        ai_meta.last_evaluated = Some(time.elapsed_seconds());
+    }
+    *last_evaluated_entity = selected.last().copied();
+}
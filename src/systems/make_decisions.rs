@@ -1,18 +1,29 @@
+use crate::ai_meta::{ActionLayer, DecisionCooldowns};
+use crate::considerations::Consideration;
 use crate::considerations::ConsiderationType;
 use crate::decisions::Filter;
 #[cfg(debug_assertions)]
 use crate::events::{ConsiderationCalculatedEvent, DecisionCalculatedEvent};
-use crate::systems::update_action::UpdateEntityActionInternalEvent;
-use crate::{AIDefinitions, AIMeta, Decision};
+use crate::overrides::AIOverrides;
+use crate::plugin::UtilityAIRng;
+use crate::systems::update_action::{ReflectAction, UpdateEntityActionInternalEvent};
+use crate::{AIDefinition, AIDefinitions, AIMeta, ActionState, Decision};
 use bevy::ecs::archetype::{Archetype, Archetypes};
 use bevy::ecs::component::Components;
 use bevy::ecs::entity::Entities;
 use bevy::log::{debug, debug_span, warn};
-use bevy::prelude::{Entity, Event, EventWriter, Query, Res};
-use bevy::utils::HashMap;
+use bevy::prelude::{Entity, Event, EventWriter, Local, Query, Res, ResMut, Time};
+use bevy::utils::{HashMap, HashSet, Parallel};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::time::Duration;
 
 pub(crate) fn make_decisions_sys(
-    mut query: Query<(Entity, &mut AIMeta)>,
+    mut query: Query<(
+        Entity,
+        &mut AIMeta,
+        Option<&ActionState>,
+        Option<&AIOverrides>,
+    )>,
     mut ew_update_entity_action: EventWriter<UpdateEntityActionInternalEvent>,
     mut ew_entity_action_changed: EventWriter<EntityActionChangedEvent>,
     #[cfg(debug_assertions)] mut ew_consideration_calculated: EventWriter<
@@ -22,257 +33,753 @@ pub(crate) fn make_decisions_sys(
         DecisionCalculatedEvent,
     >,
     ai_definitions: Res<AIDefinitions>,
+    mut rng: ResMut<UtilityAIRng>,
+    time: Res<Time>,
     archetypes: &Archetypes,
     entities: &Entities,
     components: &Components,
+    mut buffers: Local<Parallel<DecisionBuffers>>,
 ) {
     let _span = debug_span!("Making Decisions").entered();
 
-    for (entity_id, mut ai_meta) in query.iter_mut() {
-        let ai_definition = &ai_definitions.map[&ai_meta.ai_definition];
+    // each entity gets its own rng derived from this, so that the results don't depend on
+    // which thread evaluates which entity
+    let frame_seed: u64 = rng.0.gen();
+    let elapsed = time.elapsed_seconds();
 
-        let entity_archetype = archetypes
+    query
+        .par_iter_mut()
+        .for_each(|(entity_id, mut ai_meta, action_state, overrides)| {
+            make_decision(
+                entity_id,
+                &mut ai_meta,
+                action_state.copied().unwrap_or_default(),
+                overrides,
+                &ai_definitions,
+                frame_seed,
+                elapsed,
+                archetypes,
+                entities,
+                components,
+                &mut buffers.borrow_local_mut(),
+            );
+        });
+
+    for buffer in buffers.iter_mut() {
+        ew_update_entity_action.send_batch(buffer.update_entity_action.drain(..));
+        ew_entity_action_changed.send_batch(buffer.entity_action_changed.drain(..));
+        #[cfg(debug_assertions)]
+        ew_consideration_calculated.send_batch(buffer.consideration_calculated.drain(..));
+        #[cfg(debug_assertions)]
+        ew_decision_calculated.send_batch(buffer.decision_calculated.drain(..));
+    }
+}
+
+/// Events produced while making decisions, buffered per thread.
+#[derive(Default)]
+pub(crate) struct DecisionBuffers {
+    update_entity_action: Vec<UpdateEntityActionInternalEvent>,
+    entity_action_changed: Vec<EntityActionChangedEvent>,
+    #[cfg(debug_assertions)]
+    consideration_calculated: Vec<ConsiderationCalculatedEvent>,
+    #[cfg(debug_assertions)]
+    decision_calculated: Vec<DecisionCalculatedEvent>,
+}
+
+fn entity_rng(frame_seed: u64, entity_id: Entity) -> StdRng {
+    StdRng::seed_from_u64(frame_seed ^ entity_id.to_bits())
+}
+
+/// The data required to evaluate the decisions of a single entity.
+struct EvaluationContext<'w> {
+    entity_id: Entity,
+    entity_archetype: &'w Archetype,
+    ai_definitions: &'w AIDefinitions,
+    overrides: Option<&'w AIOverrides>,
+    elapsed: f32,
+    archetypes: &'w Archetypes,
+    entities: &'w Entities,
+    components: &'w Components,
+}
+
+impl EvaluationContext<'_> {
+    fn is_disabled(&self, decision: &Decision) -> bool {
+        self.overrides
+            .is_some_and(|overrides| overrides.is_disabled(decision))
+    }
+
+    fn base_score(&self, decision: &Decision) -> f32 {
+        match self.overrides {
+            Some(overrides) => overrides.base_score(decision),
+            None => decision.base_score,
+        }
+    }
+
+    fn weight(&self, consideration: &Consideration) -> f32 {
+        match self.overrides {
+            Some(overrides) => overrides.weight(consideration),
+            None => consideration.weight,
+        }
+    }
+
+    fn calculate_score(&self, consideration: &Consideration, input_score: f32) -> f32 {
+        match self.overrides {
+            Some(overrides) => overrides.calculate_score(consideration, input_score),
+            None => consideration.calculate_score(input_score),
+        }
+    }
+}
+
+/// Delegation deeper than this is assumed to be a cycle of AIDefinitions.
+pub(crate) const MAX_DELEGATION_DEPTH: usize = 8;
+
+/// Evaluates the decisions of a single entity, and changes its action if required.
+fn make_decision(
+    entity_id: Entity,
+    ai_meta: &mut AIMeta,
+    action_state: ActionState,
+    overrides: Option<&AIOverrides>,
+    ai_definitions: &AIDefinitions,
+    frame_seed: u64,
+    elapsed: f32,
+    archetypes: &Archetypes,
+    entities: &Entities,
+    components: &Components,
+    buffers: &mut DecisionBuffers,
+) {
+    if !ai_meta.evaluate_this_frame {
+        return;
+    }
+
+    ai_meta.cooldowns.remove_expired(elapsed);
+
+    let Some(ai_definition) = ai_definitions.map.get(&ai_meta.ai_definition) else {
+        debug!("Skipped as the entity's AI definition is not registered");
+        return;
+    };
+
+    let current_decision = current_decision(
+        ai_definitions,
+        layer_decisions(ai_definition, None),
+        ai_meta.current_action_reflect.as_ref(),
+        0,
+    );
+
+    // the game has signalled that the current action of the default layer is complete, so
+    // the entity has stopped performing it
+    let action_finished = current_decision.is_some() && action_state.is_finished();
+    if action_finished && !ai_meta.current_action_completed {
+        start_cooldown(
+            &mut ai_meta.cooldowns,
+            ai_meta.current_target,
+            current_decision,
+            true,
+            elapsed,
+        );
+        ai_meta.current_action_completed = true;
+    }
+
+    let ctx = EvaluationContext {
+        entity_id,
+        entity_archetype: archetypes
             .get(entities.get(entity_id).unwrap().archetype_id)
-            .unwrap();
+            .unwrap(),
+        ai_definitions,
+        overrides,
+        elapsed,
+        archetypes,
+        entities,
+        components,
+    };
+
+    let _span = debug_span!("", entity = entity_id.index()).entered();
+    let evaluated_decisions = evaluate_decisions(&ctx, ai_meta, ai_definition, buffers);
+
+    if evaluated_decisions.is_empty() {
+        debug!("No scorable considerations for decision, skipping");
+        return;
+    }
 
-        let _span = debug_span!("", entity = entity_id.index()).entered();
-        let mut evaluated_decisions = Vec::new();
+    // each layer picks its own action from its decisions
+    let mut rng = entity_rng(frame_seed, entity_id);
+    let mut layers: Vec<Option<&str>> = Vec::new();
+    for decision in &ai_definition.decisions {
+        if !layers.contains(&decision.layer.as_deref()) {
+            layers.push(decision.layer.as_deref());
+        }
+    }
 
-        for (idx, decision) in ai_definition.decisions.iter().enumerate() {
-            let _span = debug_span!("evaluating", name = decision.name).entered();
+    for layer in layers {
+        let evaluated_layer_decisions = Vec::from_iter(
+            evaluated_decisions
+                .iter()
+                .filter(|(decision, _, _)| decision.layer.as_deref() == layer)
+                .copied(),
+        );
+        if evaluated_layer_decisions.is_empty() {
+            continue;
+        }
 
-            let matches_filter = decision.subject_filters.iter().all(|filter| {
-                entity_matches_component_filter(filter, entity_archetype, components)
-            });
+        let mut action_layer = ai_meta.take_layer(layer);
+        pick_layer_action(
+            &ctx,
+            ai_meta,
+            layer,
+            &mut action_layer,
+            ai_definition,
+            evaluated_layer_decisions,
+            layer.is_none() && action_finished,
+            &mut rng,
+            buffers,
+        );
+        ai_meta.set_layer(layer, action_layer);
+    }
+}
 
-            if !matches_filter {
-                debug!("Skipped as entity does not match subject_filter");
+/// Scores the decisions of the AIDefinition for a single entity, returning the score of
+/// each decision and target.
+fn evaluate_decisions<'a>(
+    ctx: &EvaluationContext,
+    ai_meta: &mut AIMeta,
+    ai_definition: &'a AIDefinition,
+    buffers: &mut DecisionBuffers,
+) -> Vec<(&'a Decision, Option<Entity>, f32)> {
+    let mut evaluated_decisions = Vec::new();
+
+    for decision in &ai_definition.decisions {
+        let _span = debug_span!("evaluating", name = decision.name).entered();
+
+        if let Some(delegate) = decision.delegate {
+            if !ctx.ai_definitions.map.contains_key(&delegate) {
+                debug!("Skipped as the AIDefinition delegated to is not registered");
                 continue;
             }
+        }
 
-            let mut decision_score = decision.base_score;
+        if ctx.is_disabled(decision) {
+            debug!("Skipped as decision is disabled for the entity");
+            continue;
+        }
 
-            // consider simple considerations first
-            for consideration in decision
-                .considerations
-                .iter()
-                .filter(|c| c.consideration_type == ConsiderationType::Simple)
-            {
-                let consideration_input_score = *ai_meta
-                    .input_scores
-                    .get(&consideration.input)
-                    .unwrap_or(&f32::NEG_INFINITY);
-                if consideration_input_score == f32::NEG_INFINITY {
-                    debug!(
-                        "It looks like input system for '{}' hasn't run, an entity might \
-                        have components missing?",
-                        consideration.name
-                    );
-                } else {
-                    let mut consideration_score =
-                        consideration.calculate_score(consideration_input_score);
-                    if consideration_score.is_nan() {
-                        warn!(
-                            "consideration {} response curve returned NaN for input {:.2}",
-                            consideration.name,
-                            consideration_input_score
-                        );
-                        consideration_score = 0.0;
-                    }
-                    debug!(
-                        "Consideration '{}' scored: {:.2} (raw {:.2})",
-                        consideration.name,
-                        consideration_score,
-                        consideration_input_score
+        let matches_filter = decision.subject_filters.iter().all(|filter| {
+            entity_matches_component_filter(filter, ctx.entity_archetype, ctx.components)
+        });
+
+        if !matches_filter {
+            debug!("Skipped as entity does not match subject_filter");
+            continue;
+        }
+
+        if !decision.cooldown_per_target
+            && ai_meta
+                .cooldowns
+                .is_cooling_down(decision.id, None, ctx.elapsed)
+        {
+            debug!("Skipped as decision is cooling down");
+            continue;
+        }
+
+        let compensation = decision
+            .compensation
+            .unwrap_or(ai_definition.default_compensation);
+
+        // the (score, weight) of each simple consideration
+        let mut simple_scores = Vec::new();
+
+        // consider simple considerations first
+        for consideration in decision
+            .considerations
+            .iter()
+            .filter(|c| c.consideration_type == ConsiderationType::Simple)
+        {
+            let consideration_input_score = *ai_meta
+                .input_scores
+                .get(&consideration.input)
+                .unwrap_or(&f32::NEG_INFINITY);
+            if consideration_input_score == f32::NEG_INFINITY {
+                debug!(
+                    "It looks like input system for '{}' hasn't run, an entity might \
+                    have components missing?",
+                    consideration.name
+                );
+            } else {
+                let mut consideration_score =
+                    ctx.calculate_score(consideration, consideration_input_score);
+                if consideration_score.is_nan() {
+                    warn!(
+                        "consideration {} response curve returned NaN for input {:.2}",
+                        consideration.name, consideration_input_score
                     );
+                    consideration_score = 0.0;
+                }
+                debug!(
+                    "Consideration '{}' scored: {:.2} (raw {:.2})",
+                    consideration.name, consideration_score, consideration_input_score
+                );
 
-                    #[cfg(debug_assertions)]
-                    ew_consideration_calculated.send(ConsiderationCalculatedEvent {
-                        entity: entity_id,
+                #[cfg(debug_assertions)]
+                buffers
+                    .consideration_calculated
+                    .push(ConsiderationCalculatedEvent {
+                        entity: ctx.entity_id,
                         decision: decision.id,
                         consideration: consideration.id,
                         target: None,
                         score: consideration_score,
                     });
 
-                    decision_score *= consideration_score;
-                }
+                simple_scores.push((consideration_score, ctx.weight(consideration)));
             }
+        }
 
-            if !decision.is_targeted {
-                evaluated_decisions.push((idx, None, decision_score));
-                debug!("Decision score: {:.2}", decision_score);
+        if !decision.is_targeted {
+            let decision_score = ctx.base_score(decision)
+                * decision.aggregation.aggregate(&simple_scores, compensation);
+            evaluated_decisions.push((decision, None, decision_score));
+            debug!("Decision score: {:.2}", decision_score);
+
+            #[cfg(debug_assertions)]
+            buffers.decision_calculated.push(DecisionCalculatedEvent {
+                entity: ctx.entity_id,
+                decision: decision.id,
+                target: None,
+                score: decision_score,
+            });
+            continue;
+        }
 
-                #[cfg(debug_assertions)]
-                ew_decision_calculated.send(DecisionCalculatedEvent {
-                    entity: entity_id,
-                    decision: decision.id,
-                    target: None,
-                    score: decision_score,
-                });
+        let mut targeted_scores = HashMap::new();
+
+        // when the number of targets is limited, the primary consideration runs first and
+        // the remaining considerations only run for its best scoring targets
+        let primary_idx = decision
+            .max_targets
+            .and(decision.primary_consideration_idx());
+        let targeted_considerations = primary_idx
+            .map(|idx| &decision.considerations[idx])
+            .into_iter()
+            .chain(
+                decision
+                    .considerations
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, c)| {
+                        c.consideration_type == ConsiderationType::Targeted
+                            && Some(*idx) != primary_idx
+                    })
+                    .map(|(_, c)| c),
+            );
+        let mut candidates: Option<HashSet<Entity>> = None;
+
+        // consider targeted considerations
+        for (consideration_idx, consideration) in targeted_considerations.enumerate() {
+            let is_primary = primary_idx.is_some() && consideration_idx == 0;
+            let score_map = ai_meta.targeted_input_scores.get(&consideration.input);
+            if score_map.is_none() {
+                debug!(
+                    "No scores calculated yet for targeted input system {}, skipping",
+                    consideration.input_name
+                );
                 continue;
-            }
-
-            let mut targeted_scores = HashMap::new();
-
-            // consider targeted considerations
-            for consideration in decision
-                .considerations
-                .iter()
-                .filter(|c| c.consideration_type == ConsiderationType::Targeted)
-            {
-                let score_map = ai_meta.targeted_input_scores.get(&consideration.input);
-                if score_map.is_none() {
-                    debug!(
-                        "No scores calculated yet for targeted input system {}, skipping",
-                        consideration.input_name
-                    );
+            };
+            let score_map = score_map.unwrap();
+            let mut defunct_entities = Vec::new();
+            debug!("{:?}", score_map);
+            let targets: Box<dyn Iterator<Item = (&Entity, &f32)>> = match &candidates {
+                Some(candidates) => Box::new(
+                    candidates
+                        .iter()
+                        .filter_map(|entity| score_map.get_key_value(entity)),
+                ),
+                None => Box::new(score_map.iter()),
+            };
+            let mut primary_scores = Vec::new();
+            for (&target_entity_id, &consideration_input_score) in targets {
+                let _span =
+                    debug_span!("", target_entity = target_entity_id.index()).entered();
+
+                let target_entity = ctx.entities.get(target_entity_id);
+                if target_entity.is_none() {
+                    defunct_entities.push(target_entity_id);
                     continue;
-                };
-                let score_map = score_map.unwrap();
-                let mut defunct_entities = Vec::new();
-                debug!("{:?}", score_map);
-                for (&target_entity_id, &consideration_input_score) in score_map {
-                    let _span = debug_span!("", target_entity = target_entity_id.index())
-                        .entered();
-
-                    let target_entity = entities.get(target_entity_id);
-                    if target_entity.is_none() {
-                        defunct_entities.push(target_entity_id);
-                        continue;
-                    }
-                    let target_entity_archetype =
-                        archetypes.get(target_entity.unwrap().archetype_id).unwrap();
-                    let matches_filter = decision.target_filters.iter().all(|filter| {
-                        entity_matches_component_filter(
-                            filter,
-                            target_entity_archetype,
-                            components,
-                        )
-                    });
-                    if !matches_filter {
-                        debug!("Skipped as target entity does not match target_filter");
-                        continue;
-                    }
-                    let consideration_score =
-                        consideration.calculate_score(consideration_input_score);
-                    debug!(
-                        "Consideration '{}' for entity {:?} scored: {:.2} (raw {:.2})",
-                        consideration.name,
-                        target_entity_id,
-                        consideration_score,
-                        consideration_input_score
+                }
+                if decision.cooldown_per_target
+                    && ai_meta.cooldowns.is_cooling_down(
+                        decision.id,
+                        Some(target_entity_id),
+                        ctx.elapsed,
+                    )
+                {
+                    debug!("Skipped as decision is cooling down for target");
+                    continue;
+                }
+                let target_entity_archetype = ctx
+                    .archetypes
+                    .get(target_entity.unwrap().archetype_id)
+                    .unwrap();
+                let matches_filter = decision.target_filters.iter().all(|filter| {
+                    entity_matches_component_filter(
+                        filter,
+                        target_entity_archetype,
+                        ctx.components,
+                    )
+                });
+                if !matches_filter {
+                    debug!("Skipped as target entity does not match target_filter");
+                    continue;
+                }
+                let mut consideration_score =
+                    ctx.calculate_score(consideration, consideration_input_score);
+                if consideration_score.is_nan() {
+                    warn!(
+                        "consideration {} response curve returned NaN for input {:.2}",
+                        consideration.name, consideration_input_score
                     );
+                    consideration_score = 0.0;
+                }
+                debug!(
+                    "Consideration '{}' for entity {:?} scored: {:.2} (raw {:.2})",
+                    consideration.name,
+                    target_entity_id,
+                    consideration_score,
+                    consideration_input_score
+                );
 
-                    #[cfg(debug_assertions)]
-                    ew_consideration_calculated.send(ConsiderationCalculatedEvent {
-                        entity: entity_id,
+                #[cfg(debug_assertions)]
+                buffers
+                    .consideration_calculated
+                    .push(ConsiderationCalculatedEvent {
+                        entity: ctx.entity_id,
                         decision: decision.id,
                         consideration: consideration.id,
                         target: Some(target_entity_id),
                         score: consideration_score,
                     });
 
-                    *targeted_scores
-                        .entry(target_entity_id)
-                        .or_insert(decision_score) *= consideration_score;
+                targeted_scores
+                    .entry(target_entity_id)
+                    .or_insert_with(|| simple_scores.clone())
+                    .push((consideration_score, ctx.weight(consideration)));
+
+                if is_primary {
+                    primary_scores.push((target_entity_id, consideration_score));
                 }
+            }
 
-                // Tidy up despawned entities
-                for defunct_entity in &defunct_entities {
-                    let score_map = ai_meta
-                        .targeted_input_scores
-                        .get_mut(&consideration.input)
-                        .unwrap();
-                    score_map.remove(defunct_entity);
+            if let (true, Some(max_targets)) = (is_primary, decision.max_targets) {
+                if primary_scores.len() > max_targets {
+                    primary_scores.select_nth_unstable_by(max_targets - 1, |a, b| {
+                        b.1.total_cmp(&a.1)
+                    });
+                    primary_scores.truncate(max_targets);
                 }
+                let best_targets =
+                    HashSet::from_iter(primary_scores.iter().map(|(entity, _)| *entity));
+                targeted_scores.retain(|entity, _| best_targets.contains(entity));
+                debug!("Limited to {} best targets", best_targets.len());
+                candidates = Some(best_targets);
             }
 
-            for (entity, targeted_decision_score) in targeted_scores {
-                evaluated_decisions.push((idx, Some(entity), targeted_decision_score));
+            // Tidy up despawned entities
+            for defunct_entity in &defunct_entities {
+                let score_map = ai_meta
+                    .targeted_input_scores
+                    .get_mut(&consideration.input)
+                    .unwrap();
+                score_map.remove(defunct_entity);
+            }
+        }
+
+        for (entity, scores) in targeted_scores {
+            let targeted_decision_score = ctx.base_score(decision)
+                * decision.aggregation.aggregate(&scores, compensation);
+            evaluated_decisions.push((decision, Some(entity), targeted_decision_score));
+            debug!(
+                "Decision score for entity {:?}: {:.2}",
+                entity, targeted_decision_score
+            );
+
+            #[cfg(debug_assertions)]
+            buffers.decision_calculated.push(DecisionCalculatedEvent {
+                entity: ctx.entity_id,
+                decision: decision.id,
+                target: Some(entity),
+                score: targeted_decision_score,
+            });
+        }
+    }
+
+    evaluated_decisions
+}
+
+fn layer_decisions<'a>(
+    ai_definition: &'a AIDefinition,
+    layer: Option<&'a str>,
+) -> impl Iterator<Item = &'a Decision> {
+    ai_definition
+        .decisions
+        .iter()
+        .filter(move |decision| decision.layer.as_deref() == layer)
+}
+
+/// Returns the decision which inserted the current action, searching the definitions that
+/// are delegated to.
+fn current_decision<'a>(
+    ai_definitions: &'a AIDefinitions,
+    mut decisions: impl Iterator<Item = &'a Decision>,
+    current_action: Option<&ReflectAction>,
+    depth: usize,
+) -> Option<&'a Decision> {
+    let current_action = current_action?;
+    decisions.find_map(|decision| match decision.delegate {
+        Some(delegate) if depth < MAX_DELEGATION_DEPTH => current_decision(
+            ai_definitions,
+            ai_definitions.map.get(&delegate)?.decisions.iter(),
+            Some(current_action),
+            depth + 1,
+        ),
+        Some(_) => None,
+        None => decision
+            .reflect_action
+            .as_ref()
+            .filter(|action| action.is_same_action(current_action))
+            .map(|_| decision),
+    })
+}
+
+/// Whether the decision is the current decision, or delegates to it.
+fn leads_to(
+    ai_definitions: &AIDefinitions,
+    decision: &Decision,
+    current_decision: &Decision,
+    depth: usize,
+) -> bool {
+    match decision.delegate {
+        Some(delegate) if depth < MAX_DELEGATION_DEPTH => ai_definitions
+            .map
+            .get(&delegate)
+            .is_some_and(|ai_definition| {
+                ai_definition.decisions.iter().any(|decision| {
+                    leads_to(ai_definitions, decision, current_decision, depth + 1)
+                })
+            }),
+        Some(_) => false,
+        None => decision.id == current_decision.id,
+    }
+}
+
+/// Picks the action of one of the entity's action layers from its evaluated decisions, and
+/// changes it if required. When a delegating decision is picked the decisions of the
+/// definition it delegates to are evaluated and picked from instead.
+fn pick_layer_action<'a>(
+    ctx: &EvaluationContext<'a>,
+    ai_meta: &mut AIMeta,
+    layer: Option<&str>,
+    action_layer: &mut ActionLayer,
+    ai_definition: &'a AIDefinition,
+    mut evaluated_decisions: Vec<(&'a Decision, Option<Entity>, f32)>,
+    action_finished: bool,
+    rng: &mut StdRng,
+    buffers: &mut DecisionBuffers,
+) {
+    let elapsed = ctx.elapsed;
+    let current_decision = current_decision(
+        ctx.ai_definitions,
+        layer_decisions(ai_definition, layer),
+        action_layer.current_action_reflect.as_ref(),
+        0,
+    );
+    let is_current = |decision: &Decision, target: Option<Entity>| {
+        current_decision.is_some_and(|current_decision| match decision.delegate {
+            Some(_) => leads_to(ctx.ai_definitions, decision, current_decision, 0),
+            None => {
+                decision.id == current_decision.id
+                    && target == action_layer.current_target
+            }
+        })
+    };
+
+    let mut stage_definition = ai_definition;
+    let mut depth = 0;
+    let (decision, target, score) = loop {
+        // a running non-interruptible action can only be replaced by an interrupt
+        if let (false, Some(current_decision)) = (action_finished, current_decision) {
+            let is_locked = current_decision.non_interruptible
+                && evaluated_decisions
+                    .iter()
+                    .any(|(decision, target, _)| is_current(decision, *target));
+            if is_locked {
                 debug!(
-                    "Decision score for entity {:?}: {:.2}",
-                    entity, targeted_decision_score
+                    "Current action is non-interruptible, only considering interrupts"
                 );
-
-                #[cfg(debug_assertions)]
-                ew_decision_calculated.send(DecisionCalculatedEvent {
-                    entity: entity_id,
-                    decision: decision.id,
-                    target: Some(entity),
-                    score: targeted_decision_score,
+                evaluated_decisions.retain(|(decision, target, _)| {
+                    decision.is_interrupt || is_current(decision, *target)
                 });
             }
         }
 
-        if evaluated_decisions.is_empty() {
-            debug!("No scorable considerations for decision, skipping");
-            continue;
-        }
+        // buckets are picked on the raw scores, inertia only applies within the bucket
+        retain_top_bucket(stage_definition, &mut evaluated_decisions);
 
         // add inertia to current active decision
-        let current_decision_idx = ai_definition
-            .decisions
-            .iter()
-            .position(|decision| Some(decision.action) == ai_meta.current_action);
-
-        if let Some(current_decision_idx) = current_decision_idx {
-            let decision_inertia = ai_definition.decisions[current_decision_idx].intertia;
-            let inertia = decision_inertia.unwrap_or(ai_definition.default_intertia);
-            if inertia >= 0.0 {
-                if let Some(index) = evaluated_decisions.iter_mut().position(
-                    |(decision_idx, target, _)| {
-                        decision_idx == &current_decision_idx
-                            && target == &ai_meta.current_target
-                    },
-                ) {
-                    evaluated_decisions[index].2 += inertia;
+        if !action_finished {
+            if let Some((decision, _, score)) = evaluated_decisions
+                .iter_mut()
+                .find(|(decision, target, _)| is_current(decision, *target))
+            {
+                let inertia = decision
+                    .intertia
+                    .unwrap_or(stage_definition.default_intertia);
+                if inertia >= 0.0 {
+                    *score += inertia;
                 }
-            };
+            }
         }
 
-        // pick best decision
+        // pick a decision, highest scores first
         evaluated_decisions.sort_by(|a, b| b.2.total_cmp(&a.2));
-        let (decision_idx, target, score) = evaluated_decisions.first().unwrap();
-
-        let Decision {
-            action_name,
-            action,
-            ..
-        } = &ai_definition.decisions[*decision_idx];
+        let sorted_scores = Vec::from_iter(evaluated_decisions.iter().map(|e| e.2));
+        let selected = stage_definition.selection.select(&sorted_scores, rng);
+        let selected = evaluated_decisions[selected];
+
+        let Some(delegate) = selected.0.delegate else {
+            break selected;
+        };
+        if depth == MAX_DELEGATION_DEPTH {
+            warn!(
+                "Decision '{}' delegates too deeply, are its AIDefinitions cyclic?",
+                selected.0.name
+            );
+            return;
+        }
+        debug!("Delegating to {}", selected.0.name);
+        stage_definition = &ctx.ai_definitions.map[&delegate];
+        evaluated_decisions = evaluate_decisions(ctx, ai_meta, stage_definition, buffers);
+        if evaluated_decisions.is_empty() {
+            debug!("No scorable decisions for delegated AIDefinition, skipping");
+            return;
+        }
+        depth += 1;
+    };
+
+    let Decision {
+        action_name,
+        action,
+        reflect_action,
+        ..
+    } = decision;
+    let reflect_action = reflect_action
+        .as_ref()
+        .expect("only delegating decisions have no action");
+
+    let keep_current_action = action_layer
+        .current_action_reflect
+        .as_ref()
+        .is_some_and(|current| current.is_same_action(reflect_action));
+    let keep_current_target = target == action_layer.current_target;
+
+    if keep_current_action && keep_current_target && !action_finished {
+        action_layer.current_action_score = score;
+        return;
+    }
 
-        let keep_current_action = Some(*action) == ai_meta.current_action;
-        let keep_current_target = *target == ai_meta.current_target;
+    // the entity stops performing the current decision, so start its cooldown
+    if !action_finished {
+        start_cooldown(
+            &mut ai_meta.cooldowns,
+            action_layer.current_target,
+            current_decision,
+            !keep_current_action,
+            elapsed,
+        );
+    }
 
-        if keep_current_action && keep_current_target {
-            ai_meta.current_action_score = *score;
-            continue;
-        } else {
-            // Change our current action, we do this in another system as it will
-            // unfortunately require mut World access so isn't parallelisable.
-            // TODO: this could be refactored to use EntityCommands at some point
-            ew_update_entity_action.send(UpdateEntityActionInternalEvent {
-                entity_id,
-                old_action: ai_meta.current_action,
-                new_action: *action,
-                old_target: ai_meta.current_target,
-                new_target: *target,
-            });
+    // Change our current action, this is applied by the update_actions_sys using the
+    // cached reflection data for the actions.
+    buffers
+        .update_entity_action
+        .push(UpdateEntityActionInternalEvent {
+            entity_id: ctx.entity_id,
+            old_action: action_layer.current_action_reflect.clone(),
+            new_action: reflect_action.clone(),
+            old_target: action_layer.current_target,
+            new_target: target,
+            layer: layer.map(String::from),
+            restart: action_finished,
+            old_score: action_layer.current_action_score,
+            new_score: score,
+            old_duration: Duration::from_secs_f32(
+                action_layer
+                    .current_action_started
+                    .map_or(0.0, |started| (elapsed - started).max(0.0)),
+            ),
+        });
+
+    buffers
+        .entity_action_changed
+        .push(EntityActionChangedEvent {
+            entity_id: ctx.entity_id,
+            layer: layer.map(String::from),
+            prev_action: action_layer.current_action_name.clone(),
+            new_action: action_name.clone(),
+            prev_target: action_layer.current_target,
+            new_target: target,
+            prev_score: action_layer.current_action_score,
+            new_score: score,
+        });
+
+    action_layer.current_action = Some(*action);
+    action_layer.current_action_reflect = Some(reflect_action.clone());
+    action_layer.current_action_name = action_name.clone();
+    action_layer.current_action_score = score;
+    action_layer.current_target = target;
+    action_layer.current_action_completed = false;
+    if !keep_current_action || action_finished {
+        action_layer.current_action_started = Some(elapsed);
+    }
+}
 
-            ew_entity_action_changed.send(EntityActionChangedEvent {
-                entity_id,
-                prev_action: ai_meta.current_action_name.clone(),
-                new_action: action_name.clone(),
-                prev_target: ai_meta.current_target,
-                new_target: *target,
-                prev_score: ai_meta.current_action_score,
-                new_score: *score,
-            });
+/// Keeps only the decisions of the highest priority bucket with a decision scoring above the
+/// bucket's threshold, see [`Decision::in_bucket`].
+fn retain_top_bucket(
+    ai_definition: &AIDefinition,
+    evaluated_decisions: &mut Vec<(&Decision, Option<Entity>, f32)>,
+) {
+    if ai_definition.buckets.is_empty() {
+        return;
+    }
+    let top_priority = evaluated_decisions
+        .iter()
+        .filter_map(|(decision, _, score)| {
+            let (priority, threshold) = ai_definition.bucket_of(decision);
+            (*score > threshold).then_some(priority)
+        })
+        .max();
+    if let Some(top_priority) = top_priority {
+        evaluated_decisions.retain(|(decision, _, _)| {
+            ai_definition.bucket_of(decision).0 == top_priority
+        });
+    }
+}
 
-            ai_meta.current_action = Some(*action);
-            ai_meta.current_action_name = action_name.clone();
-            ai_meta.current_action_score = *score;
-            ai_meta.current_target = *target;
+/// Starts the cooldown of the entity's current decision, if it has one. Decisions with a
+/// cooldown per target always start it, others only when the action itself is stopped.
+fn start_cooldown(
+    cooldowns: &mut DecisionCooldowns,
+    current_target: Option<Entity>,
+    current_decision: Option<&Decision>,
+    action_stopped: bool,
+    elapsed: f32,
+) {
+    let Some(current_decision) = current_decision else {
+        return;
+    };
+    if let Some(cooldown) = current_decision.cooldown {
+        let expires_at = elapsed + cooldown.as_secs_f32();
+        if current_decision.cooldown_per_target {
+            cooldowns.start(current_decision.id, current_target, expires_at);
+        } else if action_stopped {
+            cooldowns.start(current_decision.id, None, expires_at);
         }
     }
 }
@@ -297,10 +804,13 @@ fn entity_matches_component_filter(
 }
 
 /// This event is for public consumption.
-/// Note that action might stay the same but target can change.
+/// Note that action might stay the same but target can change. When the entity's AI stops
+/// controlling it the new action is empty.
 #[derive(Event)]
 pub struct EntityActionChangedEvent {
     pub entity_id: Entity,
+    /// The action layer, `None` for the default layer.
+    pub layer: Option<String>,
     pub prev_action: String,
     pub new_action: String,
     pub prev_target: Option<Entity>,
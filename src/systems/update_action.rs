@@ -1,98 +1,355 @@
 use bevy::prelude::{
-    debug, debug_span, AppTypeRegistry, Entity, Event, Events, ReflectComponent,
-    ReflectDefault, World,
+    debug, debug_span, AppTypeRegistry, Commands, Component, Entity, EntityWorldMut,
+    Event, EventReader, Query, ReflectComponent, ReflectDefault, Time, World,
 };
-use std::any::TypeId;
+use bevy::reflect::{GetTypeRegistration, Reflect, TypeRegistry};
+use std::any::{type_name, TypeId};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::ActionTarget;
+use crate::ai_meta::AIMeta;
+use crate::events::{ActionCompletedEvent, ActionEnded, ActionStarted};
+use crate::systems::make_decisions::EntityActionChangedEvent;
+use crate::utils::trim_type_name;
+use crate::{ActionState, ActionTarget, LayerTargets};
 
-pub(crate) fn update_actions_sys(world: &mut World) {
+/// Issues the action & target changes decided by the AI as deferred commands, so that this
+/// system doesn't require exclusive World access.
+pub(crate) fn update_actions_sys(
+    mut commands: Commands,
+    mut events: EventReader<UpdateEntityActionInternalEvent>,
+) {
     let _span = debug_span!("Updating Actions").entered();
 
-    let type_registry = world.remove_resource::<AppTypeRegistry>().unwrap();
-
-    let mut events = world
-        .remove_resource::<Events<UpdateEntityActionInternalEvent>>()
-        .unwrap();
-
     if !events.is_empty() {
         debug!("{} Events to process", events.len());
-        let registry_read = type_registry.read();
+    }
+
+    for event in events.read() {
+        let event = event.clone();
+        commands.add(move |world: &mut World| apply_entity_action_update(event, world));
+    }
+}
+
+fn apply_entity_action_update(event: UpdateEntityActionInternalEvent, world: &mut World) {
+    let UpdateEntityActionInternalEvent {
+        entity_id,
+        old_action,
+        new_action,
+        old_target,
+        new_target,
+        layer,
+        restart,
+        old_score,
+        new_score,
+        old_duration,
+    } = event;
+
+    let _span = debug_span!("", entity = entity_id.index()).entered();
+
+    // the registry is only needed for inserting reflected values, it is cheap to clone
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+
+    let Some(mut entity_mut) = world.get_entity_mut(entity_id) else {
+        debug!("Unable to update Entity as it does not exist");
+        return;
+    };
 
-        for event in events.drain() {
-            let UpdateEntityActionInternalEvent {
+    // Update the action on the entity
+    let action_changed = restart
+        || !old_action
+            .as_ref()
+            .is_some_and(|old_action| old_action.is_same_action(&new_action));
+    if action_changed {
+        // Remove the old action component
+        if let Some(old_action) = &old_action {
+            old_action.remove(&mut entity_mut);
+            debug!("Removed Action {:?}", old_action.type_id());
+        }
+
+        // Add the new action component
+        new_action.insert(&mut entity_mut, &type_registry.read());
+        debug!("Added Action {:?}", new_action.type_id());
+
+        // named layers don't signal completion, only the default layer's action does
+        if layer.is_none() {
+            entity_mut.insert(ActionState::Running);
+        }
+    } else {
+        debug!("Action is the same as current action");
+    }
+
+    // Update the target on the entity, the targets of named layers are kept in LayerTargets
+    match &layer {
+        None if old_target != new_target => {
+            if entity_mut.contains::<ActionTarget>() {
+                entity_mut.remove::<ActionTarget>();
+                debug!("Removed Target");
+            }
+
+            if let Some(target) = new_target {
+                entity_mut.insert(ActionTarget { target });
+                debug!("Added Target {:?}", target);
+            }
+        }
+        Some(layer) if old_target != new_target => {
+            if !entity_mut.contains::<LayerTargets>() {
+                entity_mut.insert(LayerTargets::default());
+            }
+            let mut layer_targets = entity_mut.get_mut::<LayerTargets>().unwrap();
+            match new_target {
+                Some(target) => {
+                    layer_targets.targets.insert(layer.clone(), target);
+                    debug!("Added Target {:?} to layer {}", target, layer);
+                }
+                None => {
+                    layer_targets.targets.remove(layer);
+                    debug!("Removed Target from layer {}", layer);
+                }
+            }
+        }
+        _ => {}
+    }
+
+    if action_changed {
+        if let Some(old_action) = old_action {
+            (old_action.trigger_ended)(
+                world,
                 entity_id,
-                old_action,
-                new_action,
                 old_target,
-                new_target,
-            } = event;
-
-            let _span = debug_span!("", entity = entity_id.index()).entered();
-
-            if let Some(mut entity_mut) = world.get_entity_mut(entity_id) {
-                // Update the action on the entity
-                if old_action != Some(new_action) {
-                    // Remove the old action component
-                    if let Some(old_action) = old_action {
-                        registry_read
-                            .get(old_action)
-                            .unwrap()
-                            .data::<ReflectComponent>()
-                            .unwrap()
-                            .remove(&mut entity_mut);
-                        debug!("Removed Action {:?}", old_action);
-                    }
-
-                    // Add the new action component
-                    if let Some(registration) = registry_read.get(new_action) {
-                        let reflect_default =
-                            registration.data::<ReflectDefault>().unwrap();
-                        let reflect_component =
-                            registration.data::<ReflectComponent>().unwrap();
-                        reflect_component.insert(
-                            &mut entity_mut,
-                            reflect_default.default().as_ref(),
-                            &registry_read,
-                        );
-                        debug!("Added Action {:?}", new_action);
-                    } else {
-                        panic!(
-                            "An Action Component was not found in the type registry: {:?}",
-                            new_action
-                        )
-                    }
-                } else {
-                    debug!("Action is the same as current action");
-                }
+                old_score,
+                old_duration,
+            );
+        }
+        (new_action.trigger_started)(world, entity_id, new_target, new_score);
+    }
+}
 
-                // Update the target on the entity
-                if old_target != new_target {
-                    if entity_mut.contains::<ActionTarget>() {
-                        entity_mut.remove::<ActionTarget>();
-                        debug!("Removed Target");
-                    }
-
-                    if let Some(target) = new_target {
-                        entity_mut.insert(ActionTarget { target });
-                        debug!("Added Target {:?}", target);
-                    }
-                }
+/// Removes the current actions of every layer of the AIMeta from the entity, along with
+/// their targets, triggers their ActionEnded observers and sends an
+/// [`EntityActionChangedEvent`] for each. Used when the entity stops being controlled by the
+/// AI the AIMeta belongs to.
+pub(crate) fn end_entity_actions(
+    world: &mut World,
+    entity_id: Entity,
+    mut ai_meta: AIMeta,
+) {
+    let elapsed = world.resource::<Time>().elapsed_seconds();
+
+    let Some(mut entity_mut) = world.get_entity_mut(entity_id) else {
+        return;
+    };
+    entity_mut.remove::<(ActionTarget, LayerTargets, ActionState)>();
+
+    let layers = std::iter::once(None)
+        .chain(ai_meta.layers.keys().cloned().map(Some))
+        .collect::<Vec<_>>();
+    let mut ended_actions = Vec::new();
+    for layer in layers {
+        let mut action_layer = ai_meta.take_layer(layer.as_deref());
+        let Some(action) = action_layer.current_action_reflect.take() else {
+            continue;
+        };
+        action.remove(&mut entity_mut);
+        debug!("Removed Action {:?}", action.type_id());
+        let duration = Duration::from_secs_f32(
+            action_layer
+                .current_action_started
+                .map_or(0.0, |started| (elapsed - started).max(0.0)),
+        );
+        ended_actions.push((layer, action, action_layer, duration));
+    }
+
+    for (layer, action, action_layer, duration) in ended_actions {
+        let target = action_layer.current_target;
+        let score = action_layer.current_action_score;
+        (action.trigger_ended)(world, entity_id, target, score, duration);
+        world.send_event(EntityActionChangedEvent {
+            entity_id,
+            layer,
+            prev_action: action_layer.current_action_name,
+            new_action: String::new(),
+            prev_target: target,
+            new_target: None,
+            prev_score: score,
+            new_score: 0.0,
+        });
+    }
+}
+
+pub(crate) fn trigger_action_started<A: Component>(
+    world: &mut World,
+    entity_id: Entity,
+    target: Option<Entity>,
+    score: f32,
+) {
+    world.trigger_targets(
+        ActionStarted::<A> {
+            target,
+            score,
+            action: PhantomData,
+        },
+        entity_id,
+    );
+}
+
+pub(crate) fn trigger_action_ended<A: Component>(
+    world: &mut World,
+    entity_id: Entity,
+    target: Option<Entity>,
+    score: f32,
+    duration: Duration,
+) {
+    world.trigger_targets(
+        ActionEnded::<A> {
+            target,
+            score,
+            duration,
+            action: PhantomData,
+        },
+        entity_id,
+    );
+}
+
+/// Applies the [`ActionCompletedEvent`]s sent by the game to the entities' [`ActionState`].
+pub(crate) fn complete_actions_sys(
+    mut events: EventReader<ActionCompletedEvent>,
+    mut query: Query<&mut ActionState>,
+) {
+    for event in events.read() {
+        if let Ok(mut action_state) = query.get_mut(event.entity) {
+            *action_state = if event.success {
+                ActionState::Success
             } else {
-                debug!("Unable to update Entity as it does not exist");
-            }
+                ActionState::Failure
+            };
+        }
+    }
+}
+
+/// The reflection data for an Action, cached so that the type registry doesn't have to be
+/// looked up when actions change.
+#[derive(Clone)]
+pub(crate) struct ReflectAction {
+    /// The Action Component.
+    pub(crate) component: ReflectActionComponent,
+    /// Any other components inserted and removed together with the Action Component.
+    pub(crate) extra_components: Vec<ReflectActionComponent>,
+    pub(crate) trigger_started: fn(&mut World, Entity, Option<Entity>, f32),
+    pub(crate) trigger_ended: fn(&mut World, Entity, Option<Entity>, f32, Duration),
+}
+
+impl ReflectAction {
+    pub(crate) fn type_id(&self) -> TypeId {
+        self.component.type_id
+    }
+
+    pub(crate) fn components(&self) -> impl Iterator<Item = &ReflectActionComponent> {
+        std::iter::once(&self.component).chain(&self.extra_components)
+    }
+
+    /// Whether the actions insert the same components with the same values.
+    pub(crate) fn is_same_action(&self, other: &ReflectAction) -> bool {
+        self.extra_components.len() == other.extra_components.len()
+            && self
+                .components()
+                .zip(other.components())
+                .all(|(a, b)| a.is_same_component(b))
+    }
+
+    fn insert(&self, entity_mut: &mut EntityWorldMut, type_registry: &TypeRegistry) {
+        for component in self.components() {
+            component.insert(entity_mut, type_registry);
+        }
+    }
+
+    fn remove(&self, entity_mut: &mut EntityWorldMut) {
+        for component in self.components() {
+            component.reflect_component.remove(entity_mut);
+        }
+    }
+}
+
+/// The reflection data for a single component of an Action.
+#[derive(Clone)]
+pub(crate) struct ReflectActionComponent {
+    pub(crate) type_id: TypeId,
+    pub(crate) reflect_component: ReflectComponent,
+    /// Only required when the component has no prototype.
+    pub(crate) reflect_default: Option<ReflectDefault>,
+    /// The value the component is cloned from, if not inserted as its default.
+    pub(crate) prototype: Option<Arc<dyn Reflect>>,
+}
+
+impl ReflectActionComponent {
+    /// Panics if `C` doesn't reflect Component, or Default when there is no prototype.
+    pub(crate) fn of<C: Component + GetTypeRegistration>(
+        prototype: Option<Arc<dyn Reflect>>,
+    ) -> Self {
+        let name = trim_type_name(type_name::<C>());
+        let type_registration = C::get_type_registration();
+        let reflect_default = type_registration.data::<ReflectDefault>().cloned();
+        if prototype.is_none() && reflect_default.is_none() {
+            panic!("Component '{name}' must reflect Default")
+        }
+        Self {
+            type_id: TypeId::of::<C>(),
+            reflect_component: type_registration
+                .data::<ReflectComponent>()
+                .unwrap_or_else(|| panic!("Component '{name}' must reflect Component"))
+                .clone(),
+            reflect_default,
+            prototype,
         }
     }
 
-    world.insert_resource(type_registry);
-    world.insert_resource(events);
+    /// Whether the components are the same type with the same value, i.e. they are both
+    /// the default or their prototypes are equal. Prototypes are compared by value so that
+    /// re-registering an AIDefinition doesn't restart unchanged actions.
+    fn is_same_component(&self, other: &ReflectActionComponent) -> bool {
+        self.type_id == other.type_id
+            && match (&self.prototype, &other.prototype) {
+                (None, None) => true,
+                (Some(a), Some(b)) => {
+                    Arc::ptr_eq(a, b) || a.reflect_partial_eq(b.as_ref()).unwrap_or(false)
+                }
+                _ => false,
+            }
+    }
+
+    fn insert(&self, entity_mut: &mut EntityWorldMut, type_registry: &TypeRegistry) {
+        match (&self.prototype, &self.reflect_default) {
+            (Some(prototype), _) => self.reflect_component.insert(
+                entity_mut,
+                prototype.as_ref(),
+                type_registry,
+            ),
+            (None, Some(reflect_default)) => self.reflect_component.insert(
+                entity_mut,
+                reflect_default.default().as_ref(),
+                type_registry,
+            ),
+            (None, None) => {
+                unreachable!("components without a prototype reflect Default")
+            }
+        }
+    }
 }
 
-#[derive(Event)]
+#[derive(Event, Clone)]
 pub(crate) struct UpdateEntityActionInternalEvent {
     pub(crate) entity_id: Entity,
-    pub(crate) old_action: Option<TypeId>,
-    pub(crate) new_action: TypeId,
+    pub(crate) old_action: Option<ReflectAction>,
+    pub(crate) new_action: ReflectAction,
     pub(crate) old_target: Option<Entity>,
     pub(crate) new_target: Option<Entity>,
+    /// The action layer, `None` for the default layer.
+    pub(crate) layer: Option<String>,
+    /// Whether to re-insert the action even if it is unchanged, as it has completed.
+    pub(crate) restart: bool,
+    pub(crate) old_score: f32,
+    pub(crate) new_score: f32,
+    /// How long the old action ran for.
+    pub(crate) old_duration: Duration,
 }
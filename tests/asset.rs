@@ -0,0 +1,458 @@
+use std::any::TypeId;
+use std::time::Duration;
+
+use bevy::asset::{AssetPlugin, AssetServer, Assets, Handle};
+use bevy::core::TaskPoolPlugin;
+use bevy::prelude::Entity;
+use bevy::utils::default;
+
+use bevy_utility_ai::ai_meta::AIMeta;
+use bevy_utility_ai::asset::{
+    AIDefinitionAsset, AIDefinitionAssetError, UtilityAIAssetAppExt, UtilityAIAssetPlugin,
+};
+use bevy_utility_ai::considerations::Consideration;
+use bevy_utility_ai::decisions::Decision;
+use bevy_utility_ai::define_ai::DefineUtilityAI;
+use bevy_utility_ai::plugin::UtilityAIPlugin;
+use bevy_utility_ai::{input_system, targeted_input_system, AIDefinitions};
+
+use crate::common::app::test_app;
+use crate::common::{ActionOne, ActionTwo, SomeData, SomeOtherData, AA, AI};
+
+mod common;
+
+#[input_system]
+fn utility_input_low(some_data: &SomeData) -> f32 {
+    some_data.val
+}
+
+#[input_system]
+fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+    some_other_data.val
+}
+
+#[targeted_input_system]
+fn targeted_utility_input(target: (&SomeData,)) -> f32 {
+    target.0.val
+}
+
+/// This test checks that a RON asset is resolved into an AIDefinition that behaves the same
+/// as one built with DefineUtilityAI.
+#[test]
+fn ron_asset_registers_ai_definition() {
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default())
+        .register_ai_marker::<AI>()
+        .register_ai_action::<ActionOne>()
+        .register_ai_action::<ActionTwo>()
+        .register_ai_input(utility_input_low)
+        .register_ai_input(utility_input_high);
+
+    let asset = AIDefinitionAsset::from_ron(
+        br#"(
+            marker: "AI",
+            default_intertia: 0.1,
+            decisions: [
+                (
+                    action: "ActionOne",
+                    considerations: [(input: "utility_input_low")],
+                ),
+                (
+                    action: "ActionTwo",
+                    base_score: 0.5,
+                    considerations: [
+                        (
+                            input: "utility_input_high",
+                            response_curve: LinearCurve((
+                                slope: 1.0, x_shift: 0.0, y_shift: 0.0
+                            )),
+                        ),
+                    ],
+                ),
+            ],
+        )"#,
+    )
+    .unwrap();
+
+    asset.register(app.world_mut()).unwrap();
+
+    let entity_id = app
+        .world_mut()
+        .spawn((
+            SomeData { val: 0.25 },
+            SomeOtherData { val: 0.75 },
+            AI {},
+            AIMeta::new::<AI>(),
+        ))
+        .id();
+
+    app.update();
+
+    let ai_definitions = app.world().resource::<AIDefinitions>();
+    let ai_definition = &ai_definitions.map[&TypeId::of::<AI>()];
+    assert_eq!(ai_definition.decisions.len(), 2);
+    assert_eq!(ai_definition.default_intertia, 0.1);
+
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(ai_meta.current_action_score, 0.375);
+    assert_eq!(ai_meta.current_action, Some(TypeId::of::<ActionTwo>()));
+}
+
+/// This test checks that a JSON asset can be used, including targeted decisions & filters.
+#[test]
+fn json_asset_registers_targeted_ai_definition() {
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default())
+        .register_ai_marker::<AI>()
+        .register_ai_action::<ActionOne>()
+        .register_ai_filter::<AA>()
+        .register_ai_targeted_input(targeted_utility_input);
+
+    let asset = AIDefinitionAsset::from_json(
+        br#"{
+            "marker": "AI",
+            "decisions": [
+                {
+                    "action": "ActionOne",
+                    "targeted": true,
+                    "target_filters": { "include": ["AA"] },
+                    "considerations": [{ "input": "targeted_utility_input" }]
+                }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    asset.register(app.world_mut()).unwrap();
+
+    let entity_id = app.world_mut().spawn((AI {}, AIMeta::new::<AI>())).id();
+    let targets = app
+        .world_mut()
+        .spawn_batch(vec![
+            (SomeData { val: 0.9 }, AA {}),
+            (SomeData { val: 0.5 }, AA {}),
+        ])
+        .collect::<Vec<Entity>>();
+    app.world_mut().spawn(SomeData { val: 1.0 });
+
+    app.update();
+
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(ai_meta.current_action, Some(TypeId::of::<ActionOne>()));
+    assert_eq!(ai_meta.current_target, Some(targets[0]));
+}
+
+/// This test checks that an asset loaded by the AssetServer is registered, and that the
+/// AIDefinition is replaced when the asset is modified.
+#[test]
+fn loaded_asset_is_registered_and_replaced_on_modification() {
+    let mut app = test_app();
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        AssetPlugin {
+            file_path: "tests/assets".into(),
+            ..default()
+        },
+        UtilityAIPlugin::default(),
+        UtilityAIAssetPlugin,
+    ))
+    .register_ai_marker::<AI>()
+    .register_ai_action::<ActionOne>()
+    .register_ai_action::<ActionTwo>()
+    .register_ai_input(utility_input_low)
+    .register_ai_input(utility_input_high);
+
+    let handle: Handle<AIDefinitionAsset> =
+        app.world().resource::<AssetServer>().load("simple.ai.ron");
+
+    // the asset is loaded on another thread
+    for _ in 0..200 {
+        app.update();
+        if !app.world().resource::<AIDefinitions>().map.is_empty() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let ai_definitions = app.world().resource::<AIDefinitions>();
+    assert_eq!(ai_definitions.map[&TypeId::of::<AI>()].decisions.len(), 1);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((
+            SomeData { val: 0.25 },
+            SomeOtherData { val: 0.75 },
+            AI {},
+            AIMeta::new::<AI>(),
+        ))
+        .id();
+
+    app.update();
+
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(ai_meta.current_action, Some(TypeId::of::<ActionOne>()));
+
+    let modified = AIDefinitionAsset::from_ron(
+        br#"(
+            marker: "AI",
+            decisions: [
+                (
+                    action: "ActionOne",
+                    considerations: [(input: "utility_input_low")],
+                ),
+                (
+                    action: "ActionTwo",
+                    considerations: [(input: "utility_input_high")],
+                ),
+            ],
+        )"#,
+    )
+    .unwrap();
+    app.world_mut()
+        .resource_mut::<Assets<AIDefinitionAsset>>()
+        .insert(&handle, modified);
+
+    // the modified event is sent at the end of this update and handled in the next
+    app.update();
+    app.update();
+
+    let ai_definitions = app.world().resource::<AIDefinitions>();
+    assert_eq!(ai_definitions.map[&TypeId::of::<AI>()].decisions.len(), 2);
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(ai_meta.current_action, Some(TypeId::of::<ActionTwo>()));
+    assert!(app.world().get::<ActionTwo>(entity_id).is_some());
+}
+
+/// This test checks that referencing something that hasn't been registered is an error
+/// rather than a panic.
+#[test]
+fn asset_with_unknown_names_errors() {
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default())
+        .register_ai_marker::<AI>();
+
+    let asset = AIDefinitionAsset::from_ron(
+        br#"(marker: "AI", decisions: [(action: "ActionThree")])"#,
+    )
+    .unwrap();
+
+    let result = asset.register(app.world_mut());
+
+    assert!(matches!(
+        result,
+        Err(AIDefinitionAssetError::UnknownAction(name)) if name == "ActionThree"
+    ));
+    assert!(app.world().resource::<AIDefinitions>().map.is_empty());
+}
+
+/// This test checks that a response curve which its constructor would reject is an error
+/// when the asset is registered, rather than a panic when it is evaluated.
+#[test]
+fn asset_with_invalid_response_curve_errors() {
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default())
+        .register_ai_marker::<AI>()
+        .register_ai_action::<ActionOne>()
+        .register_ai_input(utility_input_low);
+
+    let asset = AIDefinitionAsset::from_ron(
+        br#"(
+            marker: "AI",
+            decisions: [
+                (
+                    action: "ActionOne",
+                    considerations: [
+                        (
+                            input: "utility_input_low",
+                            response_curve: PiecewiseLinear((points: [])),
+                        ),
+                    ],
+                ),
+            ],
+        )"#,
+    )
+    .unwrap();
+
+    let result = asset.register(app.world_mut());
+
+    assert!(matches!(result, Err(AIDefinitionAssetError::Invalid(_))));
+    assert!(app.world().resource::<AIDefinitions>().map.is_empty());
+}
+
+/// This test checks that curve parameters their constructors would reject are errors when
+/// the asset is registered.
+#[test]
+fn asset_with_invalid_curve_parameters_errors() {
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default())
+        .register_ai_marker::<AI>()
+        .register_ai_action::<ActionOne>()
+        .register_ai_input(utility_input_low);
+
+    for response_curve in [
+        "NormalCurve((mean: 0.5, std_dev: 0.0))",
+        "NormalCurve((mean: NaN, std_dev: 0.1))",
+        "SmoothstepCurve((edge0: 0.5, edge1: 0.5))",
+        "CubicBezierCurve((x1: 1.5, y1: 0.0, x2: 0.5, y2: 1.0))",
+        "Combinator(ClampInput(curve: StepCurve((threshold: 0.5, low: 0.0, high: 1.0)), \
+            min: 1.0, max: 0.0))",
+        "Combinator(ClampInput(curve: StepCurve((threshold: 0.5, low: 0.0, high: 1.0)), \
+            min: NaN, max: 1.0))",
+        "Combinator(NormalizeInput(curve: StepCurve((threshold: 0.5, low: 0.0, high: 1.0)), \
+            min: 1.0, max: 1.0))",
+        "Combinator(Multiply(StepCurve((threshold: 0.5, low: 0.0, high: 1.0)), \
+            NormalCurve((mean: 0.5, std_dev: -1.0))))",
+    ] {
+        let asset = AIDefinitionAsset::from_ron(
+            format!(
+                r#"(
+                    marker: "AI",
+                    decisions: [
+                        (
+                            action: "ActionOne",
+                            considerations: [
+                                (input: "utility_input_low", response_curve: {response_curve}),
+                            ],
+                        ),
+                    ],
+                )"#
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let result = asset.register(app.world_mut());
+
+        assert!(
+            matches!(result, Err(AIDefinitionAssetError::Invalid(_))),
+            "{response_curve} should be invalid"
+        );
+    }
+    assert!(app.world().resource::<AIDefinitions>().map.is_empty());
+}
+
+/// This test checks that a cooldown which isn't a positive number of seconds that fits in a
+/// Duration is an error rather than a panic.
+#[test]
+fn asset_with_invalid_cooldown_errors() {
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default())
+        .register_ai_marker::<AI>()
+        .register_ai_action::<ActionOne>();
+
+    for cooldown in ["1e30", "-1.0", "0.0", "inf", "NaN"] {
+        let asset = AIDefinitionAsset::from_ron(
+            format!(r#"(marker: "AI", decisions: [(action: "ActionOne", cooldown: {cooldown})])"#)
+                .as_bytes(),
+        )
+        .unwrap();
+
+        let result = asset.register(app.world_mut());
+
+        assert!(
+            matches!(result, Err(AIDefinitionAssetError::Invalid(_))),
+            "cooldown {cooldown} should be invalid"
+        );
+    }
+    assert!(app.world().resource::<AIDefinitions>().map.is_empty());
+}
+
+/// This test checks that NaN & infinite weights, input ranges, bounds and update intervals
+/// are errors when the asset is registered.
+#[test]
+fn asset_with_non_finite_numbers_errors() {
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default())
+        .register_ai_marker::<AI>()
+        .register_ai_action::<ActionOne>()
+        .register_ai_input(utility_input_low);
+
+    for (update_budget, consideration) in [
+        ("EveryFrame", "weight: NaN"),
+        ("EveryFrame", "weight: inf"),
+        ("EveryFrame", "input_range: (NaN, 1.0)"),
+        ("EveryFrame", "input_range: (0.0, inf)"),
+        ("EveryFrame", "bounds: (NaN, 1.0)"),
+        ("Interval(inf)", "weight: 1.0"),
+        ("Interval(NaN)", "weight: 1.0"),
+    ] {
+        let asset = AIDefinitionAsset::from_ron(
+            format!(
+                r#"(
+                    marker: "AI",
+                    update_budget: {update_budget},
+                    decisions: [
+                        (
+                            action: "ActionOne",
+                            considerations: [(input: "utility_input_low", {consideration})],
+                        ),
+                    ],
+                )"#
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let result = asset.register(app.world_mut());
+
+        assert!(
+            matches!(result, Err(AIDefinitionAssetError::Invalid(_))),
+            "{update_budget}, {consideration} should be invalid"
+        );
+    }
+    assert!(app.world().resource::<AIDefinitions>().map.is_empty());
+}
+
+/// This test checks that decisions in different action layers can't share an action.
+#[test]
+fn asset_with_action_in_several_layers_errors() {
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default())
+        .register_ai_marker::<AI>()
+        .register_ai_action::<ActionOne>();
+
+    let asset = AIDefinitionAsset::from_ron(
+        br#"(
+            marker: "AI",
+            decisions: [
+                (action: "ActionOne"),
+                (action: "ActionOne", layer: "vocal"),
+            ],
+        )"#,
+    )
+    .unwrap();
+
+    let result = asset.register(app.world_mut());
+
+    assert!(matches!(result, Err(AIDefinitionAssetError::Invalid(_))));
+    assert!(app.world().resource::<AIDefinitions>().map.is_empty());
+}
+
+/// This test checks that an asset can't place decisions in an action layer when its
+/// definition is delegated to.
+#[test]
+fn asset_with_layered_decision_in_delegate_errors() {
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default())
+        .register_ai_marker::<AA>()
+        .register_ai_action::<ActionOne>()
+        .register_ai_input(utility_input_low);
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::delegate::<AA>()
+                .add_consideration(Consideration::simple(utility_input_low)),
+        )
+        .register(&mut app);
+
+    let asset = AIDefinitionAsset::from_ron(
+        br#"(marker: "AA", decisions: [(action: "ActionOne", layer: "vocal")])"#,
+    )
+    .unwrap();
+
+    let result = asset.register(app.world_mut());
+
+    assert!(matches!(result, Err(AIDefinitionAssetError::Invalid(_))));
+    let ai_definitions = app.world().resource::<AIDefinitions>();
+    assert!(!ai_definitions.map.contains_key(&TypeId::of::<AA>()));
+}
@@ -1,15 +1,28 @@
 use std::any::TypeId;
+use std::time::Duration;
 
-use bevy::prelude::{Entity, Res, Time, Vec2};
+use bevy::prelude::{
+    App, Component, DetectChanges, Entity, Events, Reflect, ReflectComponent,
+    ReflectDefault, Res, ResMut, Resource, Time, Trigger, Vec2,
+};
 
 use bevy_utility_ai::ai_meta::AIMeta;
 use bevy_utility_ai::considerations::Consideration;
-use bevy_utility_ai::decisions::Decision;
+use bevy_utility_ai::decisions::{
+    ConsiderationCompensation, Decision, DecisionAggregation, DecisionSelection,
+};
 use bevy_utility_ai::define_ai::DefineUtilityAI;
-use bevy_utility_ai::plugin::UtilityAIPlugin;
-use bevy_utility_ai::response_curves::Linear;
+use bevy_utility_ai::edit_ai::UtilityAIWorldExt;
+use bevy_utility_ai::events::{ActionCompletedEvent, ActionEnded, ActionStarted};
+use bevy_utility_ai::overrides::AIOverrides;
+use bevy_utility_ai::plugin::{UtilityAIPlugin, UtilityAIRng};
+use bevy_utility_ai::response_curves::{Linear, Normal};
+use bevy_utility_ai::systems::budget::UpdateBudget;
+use bevy_utility_ai::systems::make_decisions::EntityActionChangedEvent;
 use bevy_utility_ai::utils::type_id_of;
-use bevy_utility_ai::{input_system, targeted_input_system};
+use bevy_utility_ai::{
+    input_system, targeted_input_system, AIDefinitions, ActionState, ActionTarget,
+};
 
 use crate::common::app::test_app;
 use crate::common::{
@@ -54,7 +67,7 @@ fn simple_considerations_trivial() {
         .register(&mut app);
 
     let entity_id = app
-        .world
+        .world_mut()
         .spawn((
             SomeData { val: 0.25 },
             SomeOtherData { val: 0.75 },
@@ -65,7 +78,7 @@ fn simple_considerations_trivial() {
 
     app.update();
 
-    let ai_meta = app.world.get::<AIMeta>(entity_id).unwrap();
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
 
     assert_eq!(ai_meta.current_action_score, 0.75);
     assert_eq!(ai_meta.current_action, Some(TypeId::of::<ActionTwo>()));
@@ -102,18 +115,18 @@ fn calculate_inputs_calculates_only_for_required_entities() {
         .register(&mut app);
 
     let entity_1 = app
-        .world
+        .world_mut()
         .spawn((SomeData { val: 1.0 }, AI1 {}, AIMeta::new::<AI1>()))
         .id();
     let entity_2 = app
-        .world
+        .world_mut()
         .spawn((SomeData { val: 2.0 }, AI2 {}, AIMeta::new::<AI2>()))
         .id();
 
     app.update();
 
-    let ai_meta_1 = app.world.get::<AIMeta>(entity_1).unwrap();
-    let ai_meta_2 = app.world.get::<AIMeta>(entity_2).unwrap();
+    let ai_meta_1 = app.world().get::<AIMeta>(entity_1).unwrap();
+    let ai_meta_2 = app.world().get::<AIMeta>(entity_2).unwrap();
 
     assert!(ai_meta_1
         .input_scores
@@ -154,7 +167,7 @@ fn targeted_trivial() {
         .register(&mut app);
 
     let entity_id = app
-        .world
+        .world_mut()
         .spawn((
             AI {},
             AIMeta::new::<AI>(),
@@ -165,7 +178,7 @@ fn targeted_trivial() {
         .id();
 
     let target_entitites = app
-        .world
+        .world_mut()
         .spawn_batch(vec![
             (Position {
                 val: Vec2::new(0., 0.),
@@ -178,7 +191,7 @@ fn targeted_trivial() {
 
     app.update();
 
-    let ai_meta = app.world.get::<AIMeta>(entity_id).unwrap();
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
 
     // assert that we are targeting the closest target
     assert_eq!(ai_meta.current_action, Some(TypeId::of::<ActionOne>()));
@@ -208,13 +221,13 @@ fn simple_considerations_respects_subject_filter() {
         .register(&mut app);
 
     let entity_id = app
-        .world
+        .world_mut()
         .spawn((SomeData { val: 0.25 }, AI {}, AIMeta::new::<AI>(), AA {}))
         .id();
 
     app.update();
 
-    let ai_meta = app.world.get::<AIMeta>(entity_id).unwrap();
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
 
     assert_eq!(ai_meta.current_action_score, 0.25);
     assert_eq!(ai_meta.current_action, Some(TypeId::of::<ActionOne>()));
@@ -243,13 +256,13 @@ fn simple_considerations_respects_subject_filter_two() {
         .register(&mut app);
 
     let entity_id = app
-        .world
+        .world_mut()
         .spawn((SomeData { val: 0.25 }, AI {}, AIMeta::new::<AI>()))
         .id();
 
     app.update();
 
-    let ai_meta = app.world.get::<AIMeta>(entity_id).unwrap();
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
 
     assert_eq!(ai_meta.current_action_score, -1.0);
     assert_eq!(ai_meta.current_action, None);
@@ -292,7 +305,7 @@ fn calculate_targeted_inputs_calculates_only_for_required_entities() {
         .register(&mut app);
 
     let entity_1 = app
-        .world
+        .world_mut()
         .spawn((
             Position {
                 val: Vec2::new(1.0, 1.0),
@@ -302,7 +315,7 @@ fn calculate_targeted_inputs_calculates_only_for_required_entities() {
         ))
         .id();
     let entity_2 = app
-        .world
+        .world_mut()
         .spawn((
             Position {
                 val: Vec2::new(0.0, 0.0),
@@ -314,8 +327,8 @@ fn calculate_targeted_inputs_calculates_only_for_required_entities() {
 
     app.update();
 
-    let ai_meta_1 = app.world.get::<AIMeta>(entity_1).unwrap();
-    let ai_meta_2 = app.world.get::<AIMeta>(entity_2).unwrap();
+    let ai_meta_1 = app.world().get::<AIMeta>(entity_1).unwrap();
+    let ai_meta_2 = app.world().get::<AIMeta>(entity_2).unwrap();
 
     assert!(ai_meta_1
         .targeted_input_scores
@@ -355,7 +368,7 @@ fn calculate_targeted_inputs_respects_filters_trivial() {
         .register(&mut app);
 
     let entity_subject = app
-        .world
+        .world_mut()
         .spawn((
             Position {
                 val: Vec2::new(1.0, 1.0),
@@ -365,7 +378,7 @@ fn calculate_targeted_inputs_respects_filters_trivial() {
         ))
         .id();
     let entity_target = app
-        .world
+        .world_mut()
         .spawn((
             Position {
                 val: Vec2::new(0.0, 0.0),
@@ -374,7 +387,7 @@ fn calculate_targeted_inputs_respects_filters_trivial() {
         ))
         .id();
     let _entity_ignore = app
-        .world
+        .world_mut()
         .spawn((Position {
             val: Vec2::new(-1.0, -1.0),
         },))
@@ -383,7 +396,7 @@ fn calculate_targeted_inputs_respects_filters_trivial() {
     app.update();
 
     // Assert that the only score calculated is for entity_target
-    let ai_meta = app.world.get::<AIMeta>(entity_subject).unwrap();
+    let ai_meta = app.world().get::<AIMeta>(entity_subject).unwrap();
 
     let scores = ai_meta
         .targeted_input_scores
@@ -426,7 +439,7 @@ fn calculate_targeted_inputs_respects_filters_complex() {
         .register(&mut app);
 
     let entity_subject = app
-        .world
+        .world_mut()
         .spawn((
             Position {
                 val: Vec2::new(1.0, 1.0),
@@ -436,7 +449,7 @@ fn calculate_targeted_inputs_respects_filters_complex() {
         ))
         .id();
     let entity_target = app
-        .world
+        .world_mut()
         .spawn((
             Position {
                 val: Vec2::new(0.0, 0.0),
@@ -446,7 +459,7 @@ fn calculate_targeted_inputs_respects_filters_complex() {
         ))
         .id();
     let entity_target_2 = app
-        .world
+        .world_mut()
         .spawn((
             Position {
                 val: Vec2::new(-1.0, -1.0),
@@ -455,7 +468,7 @@ fn calculate_targeted_inputs_respects_filters_complex() {
         ))
         .id();
     let _entity_ignore = app
-        .world
+        .world_mut()
         .spawn((
             Position {
                 val: Vec2::new(-1.0, -1.0),
@@ -467,7 +480,7 @@ fn calculate_targeted_inputs_respects_filters_complex() {
     app.update();
 
     // Assert that the only score calculated is for entity_target
-    let ai_meta = app.world.get::<AIMeta>(entity_subject).unwrap();
+    let ai_meta = app.world().get::<AIMeta>(entity_subject).unwrap();
 
     let scores = ai_meta
         .targeted_input_scores
@@ -513,7 +526,7 @@ fn calculate_targeted_inputs_respects_filters_overlap() {
         .register(&mut app);
 
     let entity_subject = app
-        .world
+        .world_mut()
         .spawn((
             Position {
                 val: Vec2::new(1.0, 1.0),
@@ -523,7 +536,7 @@ fn calculate_targeted_inputs_respects_filters_overlap() {
         ))
         .id();
     let _wrong_target = app
-        .world
+        .world_mut()
         .spawn((
             Position {
                 val: Vec2::new(-1.0, -1.0),
@@ -532,7 +545,7 @@ fn calculate_targeted_inputs_respects_filters_overlap() {
         ))
         .id();
     let correct_target = app
-        .world
+        .world_mut()
         .spawn((
             Position {
                 val: Vec2::new(0.5, 0.5),
@@ -544,7 +557,7 @@ fn calculate_targeted_inputs_respects_filters_overlap() {
     app.update();
 
     // Assert that the only score calculated is for entity_target
-    let ai_meta = app.world.get::<AIMeta>(entity_subject).unwrap();
+    let ai_meta = app.world().get::<AIMeta>(entity_subject).unwrap();
 
     assert_eq!(ai_meta.current_action, Some(TypeId::of::<ActionTwo>()));
     assert_eq!(ai_meta.current_target, Some(correct_target));
@@ -624,3 +637,1862 @@ fn test_systems_with_entity_args() {
 
     app.update()
 }
+
+/// This test checks that registering an AI again replaces the definition in place, keeping
+/// the entity's AIMeta and adding any newly required input systems.
+#[test]
+fn reregistering_replaces_ai_definition() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_low)),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((
+            SomeData { val: 0.25 },
+            SomeOtherData { val: 0.75 },
+            AI {},
+            AIMeta::new::<AI>(),
+        ))
+        .id();
+
+    app.update();
+
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(ai_meta.current_action, Some(TypeId::of::<ActionOne>()));
+    assert!(!ai_meta
+        .input_scores
+        .contains_key(&type_id_of(&utility_input_high)));
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_low)),
+        )
+        .add_decision(
+            Decision::simple::<ActionTwo>()
+                .add_consideration(Consideration::simple(utility_input_high)),
+        )
+        .register(&mut app);
+
+    // the entity's state survives the definition being replaced
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(ai_meta.current_action, Some(TypeId::of::<ActionOne>()));
+
+    app.update();
+
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(ai_meta.input_scores[&type_id_of(&utility_input_high)], 0.75);
+    assert_eq!(ai_meta.current_action, Some(TypeId::of::<ActionTwo>()));
+    assert!(app.world().get::<ActionTwo>(entity_id).is_some());
+    assert!(app.world().get::<ActionOne>(entity_id).is_none());
+}
+
+/// This test checks that a consideration's input range remaps raw inputs onto 0..1 before
+/// the response curve is applied.
+#[test]
+fn consideration_input_range_normalizes_input() {
+    // SETUP
+    #[input_system]
+    fn utility_input_raw(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_normalized(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(Decision::simple::<ActionOne>().add_consideration(
+            Consideration::simple(utility_input_raw).with_input_range(0.0, 100.0),
+        ))
+        .add_decision(
+            Decision::simple::<ActionTwo>()
+                .add_consideration(Consideration::simple(utility_input_normalized)),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((
+            SomeData { val: 25.0 },
+            SomeOtherData { val: 0.3 },
+            AI {},
+            AIMeta::new::<AI>(),
+        ))
+        .id();
+
+    app.update();
+
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(ai_meta.current_action_score, 0.3);
+    assert_eq!(ai_meta.current_action, Some(TypeId::of::<ActionTwo>()));
+
+    // inputs outside of the range are clamped
+    app.world_mut().get_mut::<SomeData>(entity_id).unwrap().val = 250.0;
+    app.update();
+
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(ai_meta.current_action_score, 1.0);
+    assert_eq!(ai_meta.current_action, Some(TypeId::of::<ActionOne>()));
+
+    // the range is part of the default name, but doesn't replace a custom name
+    let consideration =
+        Consideration::simple(utility_input_raw).with_input_range(0.0, 100.0);
+    assert_eq!(consideration.name, "utility_input_raw [0..100] - Linear(x)");
+    let consideration = Consideration::simple(utility_input_raw)
+        .with_name("raw")
+        .with_input_range(0.0, 100.0);
+    assert_eq!(consideration.name, "raw");
+}
+
+/// This test checks that consideration compensation stops decisions with more
+/// considerations from being unfairly punished, and that it can be overridden per decision.
+#[test]
+fn consideration_compensation() {
+    // SETUP
+    #[input_system]
+    fn utility_input_one(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_two(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    #[input_system]
+    fn utility_input_three(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    let define_ai = |compensation: ConsiderationCompensation| {
+        DefineUtilityAI::<AI>::new()
+            .set_default_compensation(compensation)
+            .add_decision(
+                Decision::simple::<ActionOne>()
+                    .add_consideration(Consideration::simple(utility_input_one)),
+            )
+            .add_decision(
+                Decision::simple::<ActionTwo>()
+                    .add_consideration(Consideration::simple(utility_input_two))
+                    .add_consideration(Consideration::simple(utility_input_three)),
+            )
+    };
+
+    let expected = [
+        (
+            ConsiderationCompensation::None,
+            TypeId::of::<ActionOne>(),
+            0.6,
+        ),
+        (
+            ConsiderationCompensation::GeometricMean,
+            TypeId::of::<ActionTwo>(),
+            0.7,
+        ),
+        (
+            ConsiderationCompensation::MakeupValue,
+            TypeId::of::<ActionTwo>(),
+            0.648,
+        ),
+    ];
+
+    for (compensation, expected_action, expected_score) in expected {
+        let mut app = test_app();
+        app.add_plugins(UtilityAIPlugin::default());
+        define_ai(compensation).register(&mut app);
+
+        let entity_id = app
+            .world_mut()
+            .spawn((
+                SomeData { val: 0.6 },
+                SomeOtherData { val: 0.7 },
+                AI {},
+                AIMeta::new::<AI>(),
+            ))
+            .id();
+
+        app.update();
+
+        let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+        assert!((ai_meta.current_action_score - expected_score).abs() < 0.0001);
+        assert_eq!(ai_meta.current_action, Some(expected_action));
+    }
+
+    // considerations whose inputs haven't been calculated aren't compensated for
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+    DefineUtilityAI::<AI>::new()
+        .set_default_compensation(ConsiderationCompensation::GeometricMean)
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_one))
+                .add_consideration(Consideration::simple(utility_input_two)),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((SomeData { val: 0.49 }, AI {}, AIMeta::new::<AI>()))
+        .id();
+
+    app.update();
+
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert!((ai_meta.current_action_score - 0.49).abs() < 0.0001);
+
+    // a decision's own compensation takes precedence over the default
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+    DefineUtilityAI::<AI>::new()
+        .set_default_compensation(ConsiderationCompensation::GeometricMean)
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_one)),
+        )
+        .add_decision(
+            Decision::simple::<ActionTwo>()
+                .set_compensation(ConsiderationCompensation::None)
+                .add_consideration(Consideration::simple(utility_input_two))
+                .add_consideration(Consideration::simple(utility_input_three)),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((
+            SomeData { val: 0.6 },
+            SomeOtherData { val: 0.7 },
+            AI {},
+            AIMeta::new::<AI>(),
+        ))
+        .id();
+
+    app.update();
+
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(ai_meta.current_action, Some(TypeId::of::<ActionOne>()));
+}
+
+/// This test checks that each decision aggregation strategy, and consideration weights, are
+/// used when scoring a decision.
+#[test]
+fn decision_aggregation() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    let expected = [
+        (
+            DecisionAggregation::Product,
+            1.0,
+            TypeId::of::<ActionTwo>(),
+            0.5,
+        ),
+        (
+            DecisionAggregation::Product,
+            0.0,
+            TypeId::of::<ActionOne>(),
+            0.729,
+        ),
+        (
+            DecisionAggregation::WeightedSum,
+            1.0,
+            TypeId::of::<ActionOne>(),
+            0.725,
+        ),
+        (
+            DecisionAggregation::Minimum,
+            1.0,
+            TypeId::of::<ActionTwo>(),
+            0.5,
+        ),
+        (
+            DecisionAggregation::fold(0.0, |acc, score, _| acc.max(score)),
+            1.0,
+            TypeId::of::<ActionOne>(),
+            0.9,
+        ),
+    ];
+
+    for (aggregation, weight, expected_action, expected_score) in expected {
+        let mut app = test_app();
+        app.add_plugins(UtilityAIPlugin::default());
+
+        DefineUtilityAI::<AI>::new()
+            .add_decision(
+                Decision::simple::<ActionOne>()
+                    .set_aggregation(aggregation)
+                    .add_consideration(
+                        Consideration::simple(utility_input_low).with_weight(weight),
+                    )
+                    .add_consideration(
+                        Consideration::simple(utility_input_high).with_weight(3.0),
+                    ),
+            )
+            .add_decision(Decision::simple::<ActionTwo>().set_base_score(0.5))
+            .register(&mut app);
+
+        let entity_id = app
+            .world_mut()
+            .spawn((
+                SomeData { val: 0.2 },
+                SomeOtherData { val: 0.9 },
+                AI {},
+                AIMeta::new::<AI>(),
+            ))
+            .id();
+
+        app.update();
+
+        let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+        assert!((ai_meta.current_action_score - expected_score).abs() < 0.0001);
+        assert_eq!(ai_meta.current_action, Some(expected_action));
+    }
+}
+
+/// This test checks that the random selection strategies pick between the candidate
+/// decisions, and that a seeded RNG makes the picks reproducible.
+#[test]
+fn decision_selection() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    let run = |selection: DecisionSelection, seed: u64| {
+        let mut app = test_app();
+        app.add_plugins(UtilityAIPlugin::default())
+            .insert_resource(UtilityAIRng::seeded(seed));
+
+        DefineUtilityAI::<AI>::new()
+            .set_selection(selection)
+            .add_decision(
+                Decision::simple::<ActionOne>()
+                    .add_consideration(Consideration::simple(utility_input_low)),
+            )
+            .add_decision(
+                Decision::simple::<ActionTwo>()
+                    .add_consideration(Consideration::simple(utility_input_high)),
+            )
+            .register(&mut app);
+
+        let entities = app
+            .world_mut()
+            .spawn_batch((0..100).map(|_| {
+                (
+                    SomeData { val: 0.5 },
+                    SomeOtherData { val: 0.6 },
+                    AI {},
+                    AIMeta::new::<AI>(),
+                )
+            }))
+            .collect::<Vec<Entity>>();
+
+        app.update();
+
+        Vec::from_iter(entities.into_iter().map(|entity| {
+            app.world()
+                .get::<AIMeta>(entity)
+                .unwrap()
+                .current_action
+                .unwrap()
+        }))
+    };
+
+    let count_action_one = |actions: &Vec<TypeId>| {
+        actions
+            .iter()
+            .filter(|a| **a == TypeId::of::<ActionOne>())
+            .count()
+    };
+
+    // highest always picks the best decision
+    let actions = run(DecisionSelection::Highest, 0);
+    assert_eq!(count_action_one(&actions), 0);
+
+    // both decisions are candidates so both should be picked by some entities
+    for selection in [
+        DecisionSelection::TopN(2),
+        DecisionSelection::WithinPercent(0.2),
+        DecisionSelection::Softmax { temperature: 0.1 },
+    ] {
+        let actions = run(selection, 0);
+        let action_one_count = count_action_one(&actions);
+        assert!(action_one_count > 0 && action_one_count < 100);
+
+        // the same seed gives the same picks
+        assert_eq!(actions, run(selection, 0));
+    }
+
+    // only the best decision is within 10% so it is always picked
+    let actions = run(DecisionSelection::WithinPercent(0.1), 0);
+    assert_eq!(count_action_one(&actions), 0);
+}
+
+/// This test checks that an update budget limits the entities evaluated per frame, in
+/// round-robin order, and that their inputs aren't calculated.
+#[test]
+fn update_budget_max_entities_per_frame() {
+    // SETUP
+    #[input_system]
+    fn utility_input(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .set_update_budget(UpdateBudget::MaxEntitiesPerFrame(2))
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input)),
+        )
+        .register(&mut app);
+
+    let entities = app
+        .world_mut()
+        .spawn_batch((0..5).map(|_| (SomeData { val: 0.5 }, AI {}, AIMeta::new::<AI>())))
+        .collect::<Vec<Entity>>();
+
+    let evaluated = |app: &bevy::app::App| {
+        entities
+            .iter()
+            .filter(|entity| {
+                let ai_meta = app.world().get::<AIMeta>(**entity).unwrap();
+                assert_eq!(
+                    ai_meta.current_action.is_some(),
+                    !ai_meta.input_scores.is_empty()
+                );
+                ai_meta.current_action.is_some()
+            })
+            .count()
+    };
+
+    app.update();
+    assert_eq!(evaluated(&app), 2);
+
+    app.update();
+    assert_eq!(evaluated(&app), 4);
+
+    app.update();
+    assert_eq!(evaluated(&app), 5);
+}
+
+/// This test checks that an interval update budget only evaluates an entity once the
+/// interval has elapsed.
+#[test]
+fn update_budget_interval() {
+    // SETUP
+    #[input_system]
+    fn utility_input(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .set_update_budget(UpdateBudget::Interval(1.0))
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input)),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((SomeData { val: 0.5 }, AI {}, AIMeta::new::<AI>()))
+        .id();
+
+    app.update();
+    assert_eq!(
+        app.world()
+            .get::<AIMeta>(entity_id)
+            .unwrap()
+            .current_action_score,
+        0.5
+    );
+
+    // not re-evaluated until the interval has passed
+    app.world_mut().get_mut::<SomeData>(entity_id).unwrap().val = 0.75;
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_secs_f32(0.5));
+    app.update();
+    assert_eq!(
+        app.world()
+            .get::<AIMeta>(entity_id)
+            .unwrap()
+            .current_action_score,
+        0.5
+    );
+
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_secs_f32(0.5));
+    app.update();
+    assert_eq!(
+        app.world()
+            .get::<AIMeta>(entity_id)
+            .unwrap()
+            .current_action_score,
+        0.75
+    );
+}
+
+/// This test checks that a decision is not scored again until its cooldown has expired
+/// after the entity stopped performing it.
+#[test]
+fn decision_cooldown() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    let define_ai = |app: &mut App| {
+        DefineUtilityAI::<AI>::new()
+            .add_decision(
+                Decision::simple::<ActionOne>()
+                    .add_consideration(Consideration::simple(utility_input_low))
+                    .with_cooldown(Duration::from_secs(1)),
+            )
+            .add_decision(
+                Decision::simple::<ActionTwo>()
+                    .add_consideration(Consideration::simple(utility_input_high)),
+            )
+            .register(app);
+    };
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+    define_ai(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((
+            SomeData { val: 0.9 },
+            SomeOtherData { val: 0.5 },
+            AI {},
+            AIMeta::new::<AI>(),
+        ))
+        .id();
+
+    app.update();
+    assert!(app.world().get::<ActionOne>(entity_id).is_some());
+
+    // switching away from ActionOne starts its cooldown
+    app.world_mut().get_mut::<SomeData>(entity_id).unwrap().val = 0.1;
+    app.update();
+    assert!(app.world().get::<ActionTwo>(entity_id).is_some());
+
+    // the cooldown survives the AI being registered again
+    define_ai(&mut app);
+    app.world_mut().get_mut::<SomeData>(entity_id).unwrap().val = 0.9;
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_secs_f32(0.5));
+    app.update();
+    assert!(app.world().get::<ActionTwo>(entity_id).is_some());
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(ai_meta.cooldowns.expires_at.len(), 1);
+
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_secs_f32(0.6));
+    app.update();
+    assert!(app.world().get::<ActionOne>(entity_id).is_some());
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert!(ai_meta.cooldowns.expires_at.is_empty());
+}
+
+/// This test checks that a non-interruptible action is kept until the game signals that it
+/// has completed, unless an interrupt out-scores it.
+#[test]
+fn non_interruptible_action() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    for interrupt in [false, true] {
+        let mut app = test_app();
+        app.add_plugins(UtilityAIPlugin::default());
+
+        let mut decision_two = Decision::simple::<ActionTwo>()
+            .add_consideration(Consideration::simple(utility_input_high));
+        if interrupt {
+            decision_two = decision_two.as_interrupt();
+        }
+
+        DefineUtilityAI::<AI>::new()
+            .add_decision(
+                Decision::simple::<ActionOne>()
+                    .add_consideration(Consideration::simple(utility_input_low))
+                    .non_interruptible(),
+            )
+            .add_decision(decision_two)
+            .register(&mut app);
+
+        let entity_id = app
+            .world_mut()
+            .spawn((
+                SomeData { val: 0.9 },
+                SomeOtherData { val: 0.5 },
+                AI {},
+                AIMeta::new::<AI>(),
+            ))
+            .id();
+
+        app.update();
+        assert!(app.world().get::<ActionOne>(entity_id).is_some());
+        assert_eq!(
+            app.world().get::<ActionState>(entity_id),
+            Some(&ActionState::Running)
+        );
+
+        app.world_mut().get_mut::<SomeData>(entity_id).unwrap().val = 0.1;
+        app.update();
+        assert_eq!(
+            app.world().get::<ActionOne>(entity_id).is_some(),
+            !interrupt
+        );
+
+        app.world_mut().send_event(ActionCompletedEvent {
+            entity: entity_id,
+            success: true,
+        });
+        app.update();
+        assert!(app.world().get::<ActionTwo>(entity_id).is_some());
+        assert_eq!(
+            app.world().get::<ActionState>(entity_id),
+            Some(&ActionState::Running)
+        );
+    }
+}
+
+/// This test checks that a completed action is restarted if it is picked again.
+#[test]
+fn completed_action_is_restarted() {
+    // SETUP
+    #[input_system]
+    fn utility_input(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input)),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((SomeData { val: 0.5 }, AI {}, AIMeta::new::<AI>()))
+        .id();
+
+    app.update();
+    assert!(app.world().get::<ActionOne>(entity_id).is_some());
+
+    *app.world_mut().get_mut::<ActionState>(entity_id).unwrap() = ActionState::Failure;
+    app.update();
+    assert!(app.world().get::<ActionOne>(entity_id).is_some());
+    assert_eq!(
+        app.world().get::<ActionState>(entity_id),
+        Some(&ActionState::Running)
+    );
+    assert_eq!(
+        app.world()
+            .resource::<Events<EntityActionChangedEvent>>()
+            .len(),
+        2
+    );
+}
+
+/// This test checks that the ActionState is only reset when the action changes, not when
+/// the entity switches to another target for the same action.
+#[test]
+fn action_state_is_kept_when_only_the_target_changes() {
+    // SETUP
+    #[targeted_input_system]
+    fn targeted_utility_input(target: (&SomeData,)) -> f32 {
+        target.0.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::targeted::<ActionOne>()
+                .add_consideration(Consideration::targeted(targeted_utility_input)),
+        )
+        .register(&mut app);
+
+    let entity_id = app.world_mut().spawn((AI {}, AIMeta::new::<AI>())).id();
+    let targets = app
+        .world_mut()
+        .spawn_batch(vec![SomeData { val: 0.9 }, SomeData { val: 0.5 }])
+        .collect::<Vec<Entity>>();
+
+    app.update();
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(ai_meta.current_target, Some(targets[0]));
+    let started = app
+        .world()
+        .entity(entity_id)
+        .get_ref::<ActionState>()
+        .unwrap()
+        .last_changed();
+
+    app.world_mut().get_mut::<SomeData>(targets[0]).unwrap().val = 0.1;
+    app.update();
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(ai_meta.current_target, Some(targets[1]));
+    let action_state = app
+        .world()
+        .entity(entity_id)
+        .get_ref::<ActionState>()
+        .unwrap();
+    assert_eq!(*action_state, ActionState::Running);
+    assert_eq!(action_state.last_changed(), started);
+}
+
+/// This test checks that typed observer triggers are fired when the AI starts and ends
+/// actions.
+#[test]
+fn action_lifecycle_observers() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    #[derive(Resource, Default)]
+    struct Lifecycle(Vec<String>);
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+    app.init_resource::<Lifecycle>();
+    app.observe(
+        |trigger: Trigger<ActionStarted<ActionOne>>, mut lifecycle: ResMut<Lifecycle>| {
+            lifecycle
+                .0
+                .push(format!("started one {:.1}", trigger.event().score));
+        },
+    );
+    app.observe(
+        |trigger: Trigger<ActionEnded<ActionOne>>, mut lifecycle: ResMut<Lifecycle>| {
+            let event = trigger.event();
+            lifecycle.0.push(format!(
+                "ended one {:.1} after {:.1}s",
+                event.score,
+                event.duration.as_secs_f32()
+            ));
+        },
+    );
+    app.observe(
+        |_trigger: Trigger<ActionStarted<ActionTwo>>,
+         mut lifecycle: ResMut<Lifecycle>| {
+            lifecycle.0.push("started two".to_string());
+        },
+    );
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_low)),
+        )
+        .add_decision(
+            Decision::simple::<ActionTwo>()
+                .add_consideration(Consideration::simple(utility_input_high)),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((
+            SomeData { val: 0.9 },
+            SomeOtherData { val: 0.5 },
+            AI {},
+            AIMeta::new::<AI>(),
+        ))
+        .id();
+
+    app.update();
+    assert_eq!(
+        app.world().resource::<Lifecycle>().0,
+        vec!["started one 0.9"]
+    );
+
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_secs(2));
+    app.world_mut().get_mut::<SomeData>(entity_id).unwrap().val = 0.1;
+    app.update();
+    assert_eq!(
+        app.world().resource::<Lifecycle>().0,
+        vec!["started one 0.9", "ended one 0.9 after 2.0s", "started two"]
+    );
+}
+
+/// This test checks that decisions sharing an action type insert their own action values.
+#[test]
+fn parameterised_actions() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component)]
+    struct ActionMove {
+        speed: f32,
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::simple_with(ActionMove { speed: 1.0 })
+                .add_consideration(Consideration::simple(utility_input_low)),
+        )
+        .add_decision(
+            Decision::simple_with(ActionMove { speed: 2.0 })
+                .add_consideration(Consideration::simple(utility_input_high)),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((
+            SomeData { val: 0.9 },
+            SomeOtherData { val: 0.5 },
+            AI {},
+            AIMeta::new::<AI>(),
+        ))
+        .id();
+
+    app.update();
+    assert_eq!(app.world().get::<ActionMove>(entity_id).unwrap().speed, 1.0);
+
+    app.world_mut().get_mut::<SomeData>(entity_id).unwrap().val = 0.1;
+    app.update();
+    assert_eq!(app.world().get::<ActionMove>(entity_id).unwrap().speed, 2.0);
+}
+
+/// This test checks that registering an unchanged AI again doesn't restart the entity's
+/// parameterised action.
+#[test]
+fn reregistering_keeps_running_parameterised_action() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component)]
+    struct ActionMove {
+        speed: f32,
+    }
+
+    #[derive(Resource, Default)]
+    struct Started(usize);
+
+    let define_ai = |app: &mut App| {
+        DefineUtilityAI::<AI>::new()
+            .add_decision(
+                Decision::simple_with(ActionMove { speed: 1.0 })
+                    .add_consideration(Consideration::simple(utility_input_low)),
+            )
+            .register(app);
+    };
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+    app.init_resource::<Started>();
+    app.observe(
+        |_trigger: Trigger<ActionStarted<ActionMove>>, mut started: ResMut<Started>| {
+            started.0 += 1;
+        },
+    );
+    define_ai(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((SomeData { val: 0.9 }, AI {}, AIMeta::new::<AI>()))
+        .id();
+
+    app.update();
+    assert_eq!(app.world().resource::<Started>().0, 1);
+
+    define_ai(&mut app);
+    app.update();
+    assert!(app.world().get::<ActionMove>(entity_id).is_some());
+    assert_eq!(app.world().resource::<Started>().0, 1);
+}
+
+/// This test checks that a decision's extra components are inserted and removed together
+/// with its action.
+#[test]
+fn action_with_extra_components() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component, Default)]
+    struct AnimationTag;
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component)]
+    struct MoveRequest {
+        speed: f32,
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_low))
+                .with_component(MoveRequest { speed: 3.0 })
+                .with_default_component::<AnimationTag>(),
+        )
+        .add_decision(
+            Decision::simple::<ActionTwo>()
+                .add_consideration(Consideration::simple(utility_input_high)),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((
+            SomeData { val: 0.9 },
+            SomeOtherData { val: 0.5 },
+            AI {},
+            AIMeta::new::<AI>(),
+        ))
+        .id();
+
+    app.update();
+    assert!(app.world().get::<ActionOne>(entity_id).is_some());
+    assert_eq!(
+        app.world().get::<MoveRequest>(entity_id).unwrap().speed,
+        3.0
+    );
+    assert!(app.world().get::<AnimationTag>(entity_id).is_some());
+
+    app.world_mut().get_mut::<SomeData>(entity_id).unwrap().val = 0.1;
+    app.update();
+    assert!(app.world().get::<ActionTwo>(entity_id).is_some());
+    assert!(app.world().get::<MoveRequest>(entity_id).is_none());
+    assert!(app.world().get::<AnimationTag>(entity_id).is_none());
+}
+
+/// This test checks that each action layer picks its own action.
+#[test]
+fn action_layers() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_low)),
+        )
+        .add_decision(
+            Decision::simple::<ActionTwo>()
+                .add_consideration(Consideration::simple(utility_input_high))
+                .in_layer("vocal"),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((
+            SomeData { val: 0.9 },
+            SomeOtherData { val: 0.5 },
+            AI {},
+            AIMeta::new::<AI>(),
+        ))
+        .id();
+
+    app.update();
+
+    // both actions are picked even though ActionOne scores higher
+    assert!(app.world().get::<ActionOne>(entity_id).is_some());
+    assert!(app.world().get::<ActionTwo>(entity_id).is_some());
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(ai_meta.current_action, Some(TypeId::of::<ActionOne>()));
+    assert_eq!(
+        ai_meta.layers["vocal"].current_action,
+        Some(TypeId::of::<ActionTwo>())
+    );
+    assert_eq!(
+        app.world()
+            .resource::<Events<EntityActionChangedEvent>>()
+            .len(),
+        2
+    );
+}
+
+/// This test checks that a delegating decision picks its action using the decisions of the
+/// AIDefinition it delegates to.
+#[test]
+fn delegate_to_sub_definition() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    #[input_system]
+    fn sub_input(some_data: &SomeData) -> f32 {
+        1.0 - some_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::delegate::<AI1>()
+                .add_consideration(Consideration::simple(utility_input_low)),
+        )
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_high)),
+        )
+        .register(&mut app);
+
+    // registered after the definition delegating to it
+    DefineUtilityAI::<AI1>::new()
+        .add_decision(
+            Decision::simple::<ActionTwo>()
+                .add_consideration(Consideration::simple(sub_input)),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((
+            SomeData { val: 0.9 },
+            SomeOtherData { val: 0.5 },
+            AI {},
+            AIMeta::new::<AI>(),
+        ))
+        .id();
+
+    app.update();
+    assert!(app.world().get::<ActionTwo>(entity_id).is_some());
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert!((ai_meta.current_action_score - 0.1).abs() < 1e-6);
+
+    app.world_mut().get_mut::<SomeData>(entity_id).unwrap().val = 0.1;
+    app.update();
+    assert!(app.world().get::<ActionOne>(entity_id).is_some());
+    assert!(app.world().get::<ActionTwo>(entity_id).is_none());
+}
+
+/// This test checks that a decision in a higher priority bucket wins over higher scoring
+/// decisions in lower buckets, as long as it scores above the bucket's threshold.
+#[test]
+fn decision_buckets() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .add_bucket("emergency", 1, 0.3)
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_low))
+                .in_bucket("emergency"),
+        )
+        .add_decision(
+            Decision::simple::<ActionTwo>()
+                .add_consideration(Consideration::simple(utility_input_high)),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((
+            SomeData { val: 0.4 },
+            SomeOtherData { val: 0.9 },
+            AI {},
+            AIMeta::new::<AI>(),
+        ))
+        .id();
+
+    app.update();
+    assert!(app.world().get::<ActionOne>(entity_id).is_some());
+
+    // below the threshold so the lower bucket is picked from
+    app.world_mut().get_mut::<SomeData>(entity_id).unwrap().val = 0.2;
+    app.update();
+    assert!(app.world().get::<ActionTwo>(entity_id).is_some());
+}
+
+/// This test checks that inertia doesn't keep a decision in its bucket once its score has
+/// dropped below the bucket's threshold.
+#[test]
+fn decision_bucket_threshold_ignores_inertia() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .set_default_intertia(0.1)
+        .add_bucket("emergency", 1, 0.3)
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_low))
+                .in_bucket("emergency"),
+        )
+        .add_decision(
+            Decision::simple::<ActionTwo>()
+                .add_consideration(Consideration::simple(utility_input_high)),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((
+            SomeData { val: 0.4 },
+            SomeOtherData { val: 0.9 },
+            AI {},
+            AIMeta::new::<AI>(),
+        ))
+        .id();
+
+    app.update();
+    assert!(app.world().get::<ActionOne>(entity_id).is_some());
+
+    // the inertia would lift the score above the threshold
+    app.world_mut().get_mut::<SomeData>(entity_id).unwrap().val = 0.25;
+    app.update();
+    assert!(app.world().get::<ActionTwo>(entity_id).is_some());
+}
+
+/// This test checks that AIOverrides change how decisions are scored for a single entity.
+#[test]
+fn per_entity_overrides() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_low))
+                .with_name("one"),
+        )
+        .add_decision(
+            Decision::simple::<ActionTwo>()
+                .add_consideration(
+                    Consideration::simple(utility_input_high).with_name("high"),
+                )
+                .with_name("two"),
+        )
+        .register(&mut app);
+
+    let overrides = [
+        None,
+        Some(AIOverrides::default().scale_base_score("one", 3.0)),
+        Some(AIOverrides::default().disable_decision("two")),
+        Some(AIOverrides::default().with_response_curve("high", Linear::new(0.1))),
+    ];
+    let entities = Vec::from_iter(overrides.into_iter().map(|overrides| {
+        let mut entity = app.world_mut().spawn((
+            SomeData { val: 0.4 },
+            SomeOtherData { val: 0.9 },
+            AI {},
+            AIMeta::new::<AI>(),
+        ));
+        if let Some(overrides) = overrides {
+            entity.insert(overrides);
+        }
+        entity.id()
+    }));
+
+    app.update();
+
+    // ASSERT
+    assert!(app.world().get::<ActionTwo>(entities[0]).is_some());
+    for entity_id in &entities[1..] {
+        assert!(app.world().get::<ActionOne>(*entity_id).is_some());
+    }
+}
+
+/// This test checks that a registered AI definition can be edited and unregistered while
+/// the app is running.
+#[test]
+fn edit_ai_definition_at_runtime() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    #[input_system]
+    fn utility_input_fixed(_some_data: &SomeData) -> f32 {
+        1.0
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_low))
+                .with_name("one"),
+        )
+        .add_decision(
+            Decision::simple::<ActionTwo>()
+                .add_consideration(
+                    Consideration::simple(utility_input_high).with_name("high"),
+                )
+                .with_name("two"),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((
+            SomeData { val: 0.4 },
+            SomeOtherData { val: 0.9 },
+            AI {},
+            AIMeta::new::<AI>(),
+        ))
+        .id();
+
+    app.update();
+    assert!(app.world().get::<ActionTwo>(entity_id).is_some());
+
+    // a flatter curve makes the second decision score below the first
+    app.world_mut()
+        .edit_ai::<AI>()
+        .unwrap()
+        .set_response_curve("two", "high", Linear::new(0.1))
+        .unwrap();
+    app.update();
+    assert!(app.world().get::<ActionOne>(entity_id).is_some());
+
+    // curves that weren't built by their constructor are validated
+    let invalid_curve = Normal {
+        mean: 0.5,
+        std_dev: 0.0,
+    };
+    let result = app
+        .world_mut()
+        .edit_ai::<AI>()
+        .unwrap()
+        .set_response_curve("two", "high", invalid_curve)
+        .map(|_| ());
+    assert!(result.is_err());
+
+    app.world_mut()
+        .edit_ai::<AI>()
+        .unwrap()
+        .remove_decision("one");
+    app.update();
+    assert!(app.world().get::<ActionTwo>(entity_id).is_some());
+    let ai_definition = &app.world().resource::<AIDefinitions>().map[&TypeId::of::<AI>()];
+    assert!(!ai_definition.requires_simple_input(&type_id_of(&utility_input_low)));
+
+    // the new decision's input system is added to the schedule
+    app.world_mut().edit_ai::<AI>().unwrap().add_decision(
+        Decision::simple::<ActionOne>()
+            .add_consideration(Consideration::simple(utility_input_fixed))
+            .with_name("fixed"),
+    );
+    app.update();
+    assert!(app.world().get::<ActionOne>(entity_id).is_some());
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(
+        ai_meta.input_scores.get(&type_id_of(&utility_input_fixed)),
+        Some(&1.0)
+    );
+
+    // entities stop performing their action once their definition is unregistered
+    assert!(app.world_mut().unregister_ai::<AI>());
+    app.update();
+    assert!(app.world().get::<ActionOne>(entity_id).is_none());
+    assert!(app.world().get::<ActionState>(entity_id).is_none());
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+    assert_eq!(ai_meta.current_action, None);
+    assert!(!app.world_mut().unregister_ai::<AI>());
+    assert!(app.world_mut().edit_ai::<AI>().is_none());
+}
+
+/// This test checks that removing the AI marker removes the entity's action and target and
+/// ends the action.
+#[test]
+fn marker_removed_ends_action() {
+    // SETUP
+    #[targeted_input_system]
+    fn utility_input_target(subject: (&Position,), target: (&Position,)) -> f32 {
+        subject.0.val.distance(target.0.val)
+    }
+
+    #[derive(Resource, Default)]
+    struct Ended(Vec<Option<Entity>>);
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+    app.init_resource::<Ended>();
+    app.observe(
+        |trigger: Trigger<ActionEnded<ActionOne>>, mut ended: ResMut<Ended>| {
+            ended.0.push(trigger.event().target);
+        },
+    );
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::targeted::<ActionOne>()
+                .add_consideration(Consideration::targeted(utility_input_target)),
+        )
+        .register(&mut app);
+
+    let target_id = app
+        .world_mut()
+        .spawn(Position {
+            val: Vec2::new(0.5, 0.0),
+        })
+        .id();
+    let entity_id = app
+        .world_mut()
+        .spawn((Position { val: Vec2::ZERO }, AI {}, AIMeta::new::<AI>()))
+        .id();
+
+    app.update();
+    assert!(app.world().get::<ActionOne>(entity_id).is_some());
+    assert!(app.world().get::<ActionTarget>(entity_id).is_some());
+
+    // ACT
+    app.world_mut().entity_mut(entity_id).remove::<AI>();
+    app.update();
+
+    // ASSERT
+    let entity = app.world().entity(entity_id);
+    assert!(!entity.contains::<AIMeta>());
+    assert!(!entity.contains::<ActionOne>());
+    assert!(!entity.contains::<ActionTarget>());
+    assert!(!entity.contains::<ActionState>());
+    assert_eq!(app.world().resource::<Ended>().0, vec![Some(target_id)]);
+
+    let events = app.world().resource::<Events<EntityActionChangedEvent>>();
+    let changed = Vec::from_iter(events.iter_current_update_events());
+    assert_eq!(changed.len(), 1);
+    assert_eq!(changed[0].prev_action, "ActionOne");
+    assert_eq!(changed[0].new_action, "");
+    assert_eq!(changed[0].prev_target, Some(target_id));
+    assert_eq!(changed[0].new_target, None);
+}
+
+/// This test checks that swapping one AI marker for another in the same frame hands the
+/// entity over to the new AI.
+#[test]
+fn swapping_ai_marker_hands_over() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI1>::new()
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_low)),
+        )
+        .register(&mut app);
+
+    DefineUtilityAI::<AI2>::new()
+        .add_decision(
+            Decision::simple::<ActionTwo>()
+                .add_consideration(Consideration::simple(utility_input_low)),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((SomeData { val: 0.5 }, AI1 {}, AIMeta::new::<AI1>()))
+        .id();
+
+    app.update();
+    assert!(app.world().get::<ActionOne>(entity_id).is_some());
+
+    // ACT
+    app.world_mut()
+        .entity_mut(entity_id)
+        .remove::<AI1>()
+        .insert(AI2 {});
+    app.update();
+
+    // ASSERT
+    let entity = app.world().entity(entity_id);
+    assert!(!entity.contains::<ActionOne>());
+    assert!(entity.contains::<ActionTwo>());
+    assert_eq!(
+        entity.get::<AIMeta>().unwrap().ai_definition,
+        TypeId::of::<AI2>()
+    );
+}
+
+/// This test checks that an action can't be used in more than one action layer, as the
+/// layers would remove it from under each other.
+#[test]
+#[should_panic]
+fn action_in_several_layers_panics() {
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_low)),
+        )
+        .add_decision(
+            Decision::simple::<ActionTwo>()
+                .add_consideration(Consideration::simple(utility_input_low))
+                .with_default_component::<ActionOne>()
+                .in_layer("vocal"),
+        )
+        .register(&mut app);
+}
+
+/// This test checks that the decisions of a definition that is delegated to can't be in an
+/// action layer of their own, whichever definition is registered first.
+#[test]
+fn layered_decision_in_delegate_panics() {
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    fn define_delegating_ai(app: &mut App) {
+        DefineUtilityAI::<AI>::new()
+            .add_decision(
+                Decision::delegate::<AI1>()
+                    .add_consideration(Consideration::simple(utility_input_low)),
+            )
+            .register(app);
+    }
+
+    fn define_layered_ai(app: &mut App) {
+        DefineUtilityAI::<AI1>::new()
+            .add_decision(
+                Decision::simple::<ActionOne>()
+                    .add_consideration(Consideration::simple(utility_input_low))
+                    .in_layer("vocal"),
+            )
+            .register(app);
+    }
+
+    let orders: [[fn(&mut App); 2]; 2] = [
+        [define_delegating_ai, define_layered_ai],
+        [define_layered_ai, define_delegating_ai],
+    ];
+    for [first, second] in orders {
+        let mut app = test_app();
+        app.add_plugins(UtilityAIPlugin::default());
+        first(&mut app);
+
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| second(&mut app)));
+        assert!(result.is_err());
+    }
+}
+
+/// This test checks that decisions added at runtime can't be in an action layer when their
+/// definition is delegated to.
+#[test]
+#[should_panic]
+fn edit_ai_layered_decision_in_delegate_panics() {
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI1>::new()
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_low)),
+        )
+        .register(&mut app);
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::delegate::<AI1>()
+                .add_consideration(Consideration::simple(utility_input_low)),
+        )
+        .register(&mut app);
+
+    app.world_mut().edit_ai::<AI1>().unwrap().add_decision(
+        Decision::simple::<ActionTwo>()
+            .add_consideration(Consideration::simple(utility_input_low))
+            .in_layer("vocal"),
+    );
+}
+
+/// This test checks that a delegating decision can't insert an action which is also used by
+/// another action layer.
+#[test]
+#[should_panic]
+fn delegate_action_in_several_layers_panics() {
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI1>::new()
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_low)),
+        )
+        .register(&mut app);
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_low)),
+        )
+        .add_decision(
+            Decision::delegate::<AI1>()
+                .add_consideration(Consideration::simple(utility_input_low))
+                .in_layer("vocal"),
+        )
+        .register(&mut app);
+}
+
+/// This test checks that decisions made in parallel across many entities each produce
+/// exactly one action change.
+#[test]
+fn make_decisions_for_many_entities() {
+    // SETUP
+    #[input_system]
+    fn utility_input_low(some_data: &SomeData) -> f32 {
+        some_data.val
+    }
+
+    #[input_system]
+    fn utility_input_high(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::simple::<ActionOne>()
+                .add_consideration(Consideration::simple(utility_input_low)),
+        )
+        .add_decision(
+            Decision::simple::<ActionTwo>()
+                .add_consideration(Consideration::simple(utility_input_high)),
+        )
+        .register(&mut app);
+
+    let entities = app
+        .world_mut()
+        .spawn_batch((0..1000).map(|idx| {
+            (
+                SomeData {
+                    val: (idx % 2) as f32,
+                },
+                SomeOtherData { val: 0.5 },
+                AI {},
+                AIMeta::new::<AI>(),
+            )
+        }))
+        .collect::<Vec<Entity>>();
+
+    app.update();
+
+    let events = app.world().resource::<Events<EntityActionChangedEvent>>();
+    assert_eq!(events.len(), 1000);
+
+    for (idx, entity) in entities.into_iter().enumerate() {
+        let ai_meta = app.world().get::<AIMeta>(entity).unwrap();
+        let expected_action = match idx % 2 {
+            0 => TypeId::of::<ActionTwo>(),
+            _ => TypeId::of::<ActionOne>(),
+        };
+        assert_eq!(ai_meta.current_action, Some(expected_action));
+    }
+}
+
+/// This test checks that action components and ActionTarget are swapped on the entity when
+/// the chosen action or target changes.
+#[test]
+fn action_and_target_swapped_on_entity() {
+    // SETUP
+    #[targeted_input_system]
+    fn targeted_utility_input(target: (&SomeData,)) -> f32 {
+        target.0.val
+    }
+
+    #[input_system]
+    fn utility_input(some_other_data: &SomeOtherData) -> f32 {
+        some_other_data.val
+    }
+
+    let mut app = test_app();
+    app.add_plugins(UtilityAIPlugin::default());
+
+    DefineUtilityAI::<AI>::new()
+        .add_decision(
+            Decision::targeted::<ActionOne>()
+                .add_consideration(Consideration::targeted(targeted_utility_input)),
+        )
+        .add_decision(
+            Decision::simple::<ActionTwo>()
+                .add_consideration(Consideration::simple(utility_input)),
+        )
+        .register(&mut app);
+
+    let entity_id = app
+        .world_mut()
+        .spawn((SomeOtherData { val: 0.1 }, AI {}, AIMeta::new::<AI>()))
+        .id();
+    let target_one = app.world_mut().spawn(SomeData { val: 0.5 }).id();
+    let target_two = app.world_mut().spawn(SomeData { val: 0.3 }).id();
+
+    app.update();
+
+    assert!(app.world().get::<ActionOne>(entity_id).is_some());
+    assert_eq!(
+        app.world().get::<ActionTarget>(entity_id).unwrap().target,
+        target_one
+    );
+
+    // change target only
+    app.world_mut().get_mut::<SomeData>(target_two).unwrap().val = 0.7;
+    app.update();
+
+    assert!(app.world().get::<ActionOne>(entity_id).is_some());
+    assert_eq!(
+        app.world().get::<ActionTarget>(entity_id).unwrap().target,
+        target_two
+    );
+
+    // change to a simple action
+    app.world_mut()
+        .get_mut::<SomeOtherData>(entity_id)
+        .unwrap()
+        .val = 0.9;
+    app.update();
+
+    assert!(app.world().get::<ActionOne>(entity_id).is_none());
+    assert!(app.world().get::<ActionTwo>(entity_id).is_some());
+    assert!(app.world().get::<ActionTarget>(entity_id).is_none());
+}
+
+/// This test checks that limiting a decision's targets only keeps the best targets
+/// according to its primary consideration.
+#[test]
+fn max_targets_limits_to_best_primary_targets() {
+    // SETUP
+    #[targeted_input_system]
+    fn targeted_input_one(target: (&SomeData,)) -> f32 {
+        target.0.val
+    }
+
+    #[targeted_input_system]
+    fn targeted_input_two(target: (&SomeOtherData,)) -> f32 {
+        target.0.val
+    }
+
+    let run = |decision: Decision| {
+        let mut app = test_app();
+        app.add_plugins(UtilityAIPlugin::default());
+
+        DefineUtilityAI::<AI>::new()
+            .add_decision(decision)
+            .register(&mut app);
+
+        let entity_id = app
+            .world_mut()
+            .spawn((Position { val: Vec2::ZERO }, AI {}, AIMeta::new::<AI>()))
+            .id();
+        let targets = app
+            .world_mut()
+            .spawn_batch(vec![
+                (SomeData { val: 0.9 }, SomeOtherData { val: 0.3 }),
+                (SomeData { val: 0.8 }, SomeOtherData { val: 0.5 }),
+                (SomeData { val: 0.5 }, SomeOtherData { val: 1.0 }),
+                (SomeData { val: 0.1 }, SomeOtherData { val: 0.1 }),
+            ])
+            .collect::<Vec<Entity>>();
+
+        app.update();
+
+        let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
+        let target_idx = targets
+            .iter()
+            .position(|t| Some(*t) == ai_meta.current_target)
+            .unwrap();
+        (target_idx, ai_meta.current_action_score)
+    };
+
+    // without a limit the best combined target is picked
+    let (target_idx, score) = run(Decision::targeted::<ActionOne>()
+        .add_consideration(Consideration::targeted(targeted_input_one))
+        .add_consideration(Consideration::targeted(targeted_input_two)));
+    assert_eq!((target_idx, score), (2, 0.5));
+
+    // the first targeted consideration is the primary one by default
+    let (target_idx, score) = run(Decision::targeted::<ActionOne>()
+        .max_targets(2)
+        .add_consideration(Consideration::targeted(targeted_input_one))
+        .add_consideration(Consideration::targeted(targeted_input_two)));
+    assert_eq!((target_idx, score), (1, 0.4));
+
+    let (target_idx, score) = run(Decision::targeted::<ActionOne>()
+        .max_targets(1)
+        .add_consideration(Consideration::targeted(targeted_input_one))
+        .add_primary_consideration(Consideration::targeted(targeted_input_two)));
+    assert_eq!((target_idx, score), (2, 0.5));
+}
@@ -48,7 +48,7 @@ fn input_system_macro_updates_aimeta_inputs() {
 
     app.init_resource::<AIDefinitions>();
 
-    let mut ai_definitions = app.world.resource_mut::<AIDefinitions>();
+    let mut ai_definitions = app.world_mut().resource_mut::<AIDefinitions>();
     ai_definitions.map.insert(
         TypeId::of::<AI>(),
         AIDefinition {
@@ -58,17 +58,21 @@ fn input_system_macro_updates_aimeta_inputs() {
             simple_inputs: HashSet::from_iter(vec![type_id_of(&utility_input_low)]),
             targeted_inputs: Default::default(),
             default_intertia: 0.0,
+            default_compensation: Default::default(),
+            selection: Default::default(),
+            buckets: Default::default(),
+            update_budget: Default::default(),
         },
     );
 
     let entity_id = app
-        .world
+        .world_mut()
         .spawn((SomeData { val: 0.25 }, AI {}, AIMeta::new::<AI>()))
         .id();
 
     app.update();
 
-    let ai_meta = app.world.get::<AIMeta>(entity_id).unwrap();
+    let ai_meta = app.world().get::<AIMeta>(entity_id).unwrap();
 
     assert!(ai_meta
         .input_scores
@@ -2,10 +2,11 @@ mod common;
 
 use crate::common::{SomeOtherData, AA};
 use bevy::app::Update;
-use bevy::prelude::Res;
+use bevy::prelude::{Res, Transform, Vec3};
 use bevy::{app::App, prelude::Time, utils::HashMap};
 use bevy_utility_ai::decisions::Filter;
 use bevy_utility_ai::events::InputCalculatedEvent;
+use bevy_utility_ai::spatial::{SpatialIndex, TransformGrid};
 use bevy_utility_ai::utils::type_id_of;
 use bevy_utility_ai::{AIDefinition, AIDefinitions, AIMeta};
 use bevy_utility_ai::{FilterDefinition, TargetedInputRequirements};
@@ -82,7 +83,7 @@ fn trivial_targeted_input_system_updates_aimeta_inputs() {
     app.add_systems(Update, trivial_targeted_input);
     app.add_event::<InputCalculatedEvent>();
 
-    let mut ai_definitions = app.world.resource_mut::<AIDefinitions>();
+    let mut ai_definitions = app.world_mut().resource_mut::<AIDefinitions>();
     ai_definitions.map.insert(
         TypeId::of::<AI>(),
         AIDefinition {
@@ -97,15 +98,19 @@ fn trivial_targeted_input_system_updates_aimeta_inputs() {
                 },
             )]),
             default_intertia: 0.0,
+            default_compensation: Default::default(),
+            selection: Default::default(),
+            buckets: Default::default(),
+            update_budget: Default::default(),
         },
     );
 
-    let subject_entity_id = app.world.spawn((AI {}, AIMeta::new::<AI>())).id();
-    let target_entity_id = app.world.spawn(SomeData { val: 0.25 }).id();
+    let subject_entity_id = app.world_mut().spawn((AI {}, AIMeta::new::<AI>())).id();
+    let target_entity_id = app.world_mut().spawn(SomeData { val: 0.25 }).id();
 
     app.update();
 
-    let ai_meta = app.world.get::<AIMeta>(subject_entity_id).unwrap();
+    let ai_meta = app.world().get::<AIMeta>(subject_entity_id).unwrap();
 
     assert_eq!(ai_meta.targeted_input_scores.len(), 1);
     assert!(ai_meta
@@ -130,7 +135,7 @@ fn targeted_input_system_updates_aimeta_inputs() {
     app.add_systems(Update, targeted_input);
     app.add_event::<InputCalculatedEvent>();
 
-    let mut ai_definitions = app.world.resource_mut::<AIDefinitions>();
+    let mut ai_definitions = app.world_mut().resource_mut::<AIDefinitions>();
     ai_definitions.map.insert(
         TypeId::of::<AI>(),
         AIDefinition {
@@ -145,18 +150,22 @@ fn targeted_input_system_updates_aimeta_inputs() {
                 },
             )]),
             default_intertia: 0.0,
+            default_compensation: Default::default(),
+            selection: Default::default(),
+            buckets: Default::default(),
+            update_budget: Default::default(),
         },
     );
 
     let subject_entity_id = app
-        .world
+        .world_mut()
         .spawn((SomeOtherData { val: 0.75 }, AI {}, AIMeta::new::<AI>()))
         .id();
-    let target_entity_id = app.world.spawn(SomeData { val: 0.25 }).id();
+    let target_entity_id = app.world_mut().spawn(SomeData { val: 0.25 }).id();
 
     app.update();
 
-    let ai_meta = app.world.get::<AIMeta>(subject_entity_id).unwrap();
+    let ai_meta = app.world().get::<AIMeta>(subject_entity_id).unwrap();
 
     assert_eq!(ai_meta.targeted_input_scores.len(), 1);
     assert!(ai_meta
@@ -179,7 +188,7 @@ fn trivial_targeted_input_system_respects_filter_set() {
     app.add_systems(Update, trivial_targeted_input);
     app.add_event::<InputCalculatedEvent>();
 
-    let mut ai_definitions = app.world.resource_mut::<AIDefinitions>();
+    let mut ai_definitions = app.world_mut().resource_mut::<AIDefinitions>();
     ai_definitions.map.insert(
         TypeId::of::<AI>(),
         AIDefinition {
@@ -196,17 +205,21 @@ fn trivial_targeted_input_system_respects_filter_set() {
                 },
             )]),
             default_intertia: 0.0,
+            default_compensation: Default::default(),
+            selection: Default::default(),
+            buckets: Default::default(),
+            update_budget: Default::default(),
         },
     );
 
     // spawn some entities
-    let entity_subject = app.world.spawn((AI {}, AIMeta::new::<AI>())).id();
-    let entity_ignore = app.world.spawn(SomeData { val: 0.25 }).id();
-    let entity_target = app.world.spawn((SomeData { val: 0.75 }, AA {})).id();
+    let entity_subject = app.world_mut().spawn((AI {}, AIMeta::new::<AI>())).id();
+    let entity_ignore = app.world_mut().spawn(SomeData { val: 0.25 }).id();
+    let entity_target = app.world_mut().spawn((SomeData { val: 0.75 }, AA {})).id();
 
     app.update();
 
-    let ai_meta = app.world.get::<AIMeta>(entity_subject).unwrap();
+    let ai_meta = app.world().get::<AIMeta>(entity_subject).unwrap();
 
     assert_eq!(ai_meta.targeted_input_scores.len(), 1);
     assert!(ai_meta
@@ -222,3 +235,131 @@ fn trivial_targeted_input_system_respects_filter_set() {
             .contains_key(&entity_ignore)
     );
 }
+
+#[test]
+fn targeted_input_system_with_max_range_only_scores_targets_in_range() {
+    #[targeted_input_system(max_range = 10.0)]
+    fn ranged_targeted_input(target: (&SomeData,)) -> f32 {
+        target.0.val
+    }
+
+    // with & without the TransformGrid spatial index
+    for use_grid in [false, true] {
+        let mut app = test_app();
+
+        app.add_systems(Update, ranged_targeted_input);
+        app.add_event::<InputCalculatedEvent>();
+        if use_grid {
+            app.insert_resource(TransformGrid::new(4.0));
+        }
+
+        let mut ai_definitions = app.world_mut().resource_mut::<AIDefinitions>();
+        ai_definitions.map.insert(
+            TypeId::of::<AI>(),
+            AIDefinition {
+                name: "foo".to_string(),
+                marker_type: TypeId::of::<AI>(),
+                decisions: vec![], // this field doesn't matter for this test
+                simple_inputs: Default::default(),
+                targeted_inputs: HashMap::from_iter(vec![(
+                    type_id_of(&ranged_targeted_input),
+                    TargetedInputRequirements {
+                        target_filter: FilterDefinition::Any,
+                    },
+                )]),
+                default_intertia: 0.0,
+                default_compensation: Default::default(),
+                selection: Default::default(),
+                buckets: Default::default(),
+                update_budget: Default::default(),
+            },
+        );
+
+        let subject_entity_id = app
+            .world_mut()
+            .spawn((AI {}, AIMeta::new::<AI>(), Transform::default()))
+            .id();
+        let near_entity_id = app
+            .world_mut()
+            .spawn((SomeData { val: 0.25 }, Transform::from_xyz(3.0, 4.0, 0.0)))
+            .id();
+        let far_entity_id = app
+            .world_mut()
+            .spawn((SomeData { val: 0.5 }, Transform::from_xyz(20.0, 0.0, 0.0)))
+            .id();
+
+        if use_grid {
+            let mut grid = app.world_mut().resource_mut::<TransformGrid>();
+            grid.insert(subject_entity_id, Vec3::ZERO);
+            grid.insert(near_entity_id, Vec3::new(3.0, 4.0, 0.0));
+            grid.insert(far_entity_id, Vec3::new(20.0, 0.0, 0.0));
+        }
+        app.update();
+
+        let ai_meta = app.world().get::<AIMeta>(subject_entity_id).unwrap();
+        let scores = &ai_meta.targeted_input_scores[&type_id_of(&ranged_targeted_input)];
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores[&near_entity_id], 0.25);
+
+        // once a target moves out of range its score is forgotten
+        app.world_mut()
+            .get_mut::<Transform>(near_entity_id)
+            .unwrap()
+            .translation = Vec3::new(30.0, 0.0, 0.0);
+        if use_grid {
+            let mut grid = app.world_mut().resource_mut::<TransformGrid>();
+            grid.clear();
+            grid.insert(subject_entity_id, Vec3::ZERO);
+            grid.insert(near_entity_id, Vec3::new(30.0, 0.0, 0.0));
+            grid.insert(far_entity_id, Vec3::new(20.0, 0.0, 0.0));
+        }
+        app.update();
+
+        let ai_meta = app.world().get::<AIMeta>(subject_entity_id).unwrap();
+        assert!(
+            ai_meta.targeted_input_scores[&type_id_of(&ranged_targeted_input)].is_empty()
+        );
+
+        // a subject without a Transform has no targets in range
+        app.world_mut()
+            .get_mut::<Transform>(far_entity_id)
+            .unwrap()
+            .translation = Vec3::new(5.0, 0.0, 0.0);
+        if use_grid {
+            let mut grid = app.world_mut().resource_mut::<TransformGrid>();
+            grid.insert(far_entity_id, Vec3::new(5.0, 0.0, 0.0));
+        }
+        app.update();
+
+        let ai_meta = app.world().get::<AIMeta>(subject_entity_id).unwrap();
+        let scores = &ai_meta.targeted_input_scores[&type_id_of(&ranged_targeted_input)];
+        assert_eq!(scores.len(), 1);
+
+        app.world_mut()
+            .entity_mut(subject_entity_id)
+            .remove::<Transform>();
+        app.update();
+
+        let ai_meta = app.world().get::<AIMeta>(subject_entity_id).unwrap();
+        assert!(
+            ai_meta.targeted_input_scores[&type_id_of(&ranged_targeted_input)].is_empty()
+        );
+    }
+}
+
+#[test]
+fn transform_grid_finds_entities_within_range() {
+    let mut grid = TransformGrid::new(1.0);
+    let entities = Vec::from_iter((0..100).map(|idx| {
+        let entity = bevy::prelude::Entity::from_raw(idx);
+        grid.insert(entity, Vec3::new(idx as f32 * 0.5, 0.0, 0.0));
+        entity
+    }));
+
+    let mut found = grid.entities_within(Vec3::new(10.0, 0.0, 0.0), 2.0);
+    found.sort();
+    assert_eq!(found, entities[16..=24]);
+
+    // a range larger than the grid visits every cell
+    assert_eq!(grid.entities_within(Vec3::ZERO, 1000.0).len(), 100);
+}